@@ -12,6 +12,7 @@ async fn main() -> anyhow::Result<()> {
         n: Some(1),
         size: Some("1024x1024".into()),
         response_format: Some(ImageResponseFormat::Url),
+        ..Default::default()
     };
     let resp = client.images_generate(req).await?;
     if let Some(img) = resp.data.first() {
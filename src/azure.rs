@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+/// Endpoints that Azure OpenAI scopes under `/openai/deployments/{deployment}/`.
+/// Everything else (files, responses, ...) lives directly under `/openai/`.
+const DEPLOYMENT_SCOPED: &[&str] = &[
+    "chat/completions",
+    "completions",
+    "embeddings",
+    "images/generations",
+    "audio/speech",
+    "audio/transcriptions",
+    "audio/translations",
+];
+
+/// How requests authenticate against an Azure OpenAI resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AzureAuth {
+    /// Send the key in the `api-key` header.
+    #[default]
+    ApiKey,
+    /// Send a Microsoft Entra ID token as `Authorization: Bearer`.
+    EntraId,
+}
+
+/// Azure OpenAI settings for [`crate::OpenAI`].
+///
+/// The client's base URL is the resource endpoint, e.g.
+/// `https://my-resource.openai.azure.com`. Model names on requests are
+/// mapped to deployment names; a model without an explicit mapping is used
+/// as the deployment name unchanged.
+#[derive(Debug, Clone)]
+pub struct AzureConfig {
+    api_version: String,
+    deployments: HashMap<String, String>,
+    auth: AzureAuth,
+}
+
+impl AzureConfig {
+    pub fn new<S: Into<String>>(api_version: S) -> Self {
+        Self {
+            api_version: api_version.into(),
+            deployments: HashMap::new(),
+            auth: AzureAuth::default(),
+        }
+    }
+
+    /// Route requests for `model` to `deployment`.
+    pub fn deployment<M: Into<String>, D: Into<String>>(mut self, model: M, deployment: D) -> Self {
+        self.deployments.insert(model.into(), deployment.into());
+        self
+    }
+
+    pub fn auth(mut self, auth: AzureAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    pub fn api_version(&self) -> &str {
+        &self.api_version
+    }
+
+    pub fn auth_mode(&self) -> AzureAuth {
+        self.auth
+    }
+
+    pub fn deployment_for<'a>(&'a self, model: &'a str) -> &'a str {
+        self.deployments
+            .get(model)
+            .map(String::as_str)
+            .unwrap_or(model)
+    }
+
+    // Translate an OpenAI path such as `/v1/chat/completions` into the Azure
+    // path. Deployment-scoped endpoints need a model to pick the deployment.
+    pub(crate) fn path_for(&self, path: &str, model: Option<&str>) -> String {
+        let rest = path.trim_start_matches('/');
+        let rest = rest.strip_prefix("v1/").unwrap_or(rest);
+        match model {
+            Some(model) if DEPLOYMENT_SCOPED.contains(&rest) => {
+                format!(
+                    "/openai/deployments/{}/{}",
+                    self.deployment_for(model),
+                    rest
+                )
+            }
+            _ => format!("/openai/{}", rest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AzureConfig;
+
+    #[test]
+    fn maps_paths_and_deployments() {
+        let cfg = AzureConfig::new("2024-10-21").deployment("gpt-4o", "prod-4o");
+        assert_eq!(
+            cfg.path_for("/v1/chat/completions", Some("gpt-4o")),
+            "/openai/deployments/prod-4o/chat/completions"
+        );
+        assert_eq!(
+            cfg.path_for("/v1/embeddings", Some("text-embedding-3-small")),
+            "/openai/deployments/text-embedding-3-small/embeddings"
+        );
+        assert_eq!(
+            cfg.path_for("/v1/files/file-1/content", None),
+            "/openai/files/file-1/content"
+        );
+        assert_eq!(
+            cfg.path_for("/v1/responses", Some("gpt-4o")),
+            "/openai/responses"
+        );
+    }
+}
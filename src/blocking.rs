@@ -0,0 +1,340 @@
+//! A synchronous client for code that does not run an async runtime.
+//!
+//! [`OpenAI`] wraps the async [`crate::OpenAI`] and drives it on a private
+//! single-threaded tokio runtime, so requests, retries, middleware and the
+//! types in [`crate::types`] are the same. Streaming methods return a
+//! [`StreamIter`], a plain [`Iterator`] over the stream's items.
+//!
+//! Calls block the current thread and must not be made from inside an
+//! async runtime; use the async client there.
+//!
+//! ```no_run
+//! use openai_sdk_rs::blocking::OpenAI;
+//! use openai_sdk_rs::types::chat::{ChatCompletionRequest, ChatMessage};
+//!
+//! let client = OpenAI::from_env()?;
+//! let req = ChatCompletionRequest {
+//!     model: "gpt-4o-mini".into(),
+//!     messages: vec![ChatMessage::user("Say hi")],
+//!     ..Default::default()
+//! };
+//! for chunk in client.chat_completion_stream(req)? {
+//!     print!("{}", chunk?.choices[0].delta.content.as_deref().unwrap_or(""));
+//! }
+//! # Ok::<(), openai_sdk_rs::Error>(())
+//! ```
+
+use std::sync::Arc;
+
+use futures_util::StreamExt;
+use tokio::runtime::Runtime;
+
+use crate::client::OpenAIBuilder;
+use crate::error::Error;
+use crate::meta::RawResponse;
+use crate::options::RequestOptions;
+use crate::types::chat::{ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse};
+use crate::types::embeddings::{EmbeddingsRequest, EmbeddingsResponse};
+use crate::types::files::{FileDeleteResponse, FileListResponse, FileObject};
+use crate::types::images::{ImageGenerationRequest, ImageGenerationResponse};
+use crate::types::responses::{ResponseStreamEvent, ResponsesRequest, ResponsesResponse};
+use crate::utils::BoxStream;
+
+// Blocking wrappers around async methods of the same name and arguments.
+macro_rules! blocking_methods {
+    ($($name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            pub fn $name(&self, $($arg: $ty),*) -> Result<$ret, Error> {
+                self.rt.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// Synchronous counterpart of [`crate::OpenAI`].
+#[derive(Clone)]
+pub struct OpenAI {
+    inner: crate::OpenAI,
+    rt: Arc<Runtime>,
+}
+
+impl std::fmt::Debug for OpenAI {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenAI")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OpenAI {
+    /// Wrap an async client.
+    pub fn from_async(inner: crate::OpenAI) -> Result<Self, Error> {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            inner,
+            rt: Arc::new(rt),
+        })
+    }
+
+    pub fn new<S: Into<String>>(api_key: S) -> Result<Self, Error> {
+        Self::from_async(crate::OpenAI::new(api_key)?)
+    }
+
+    #[cfg(feature = "reqwest")]
+    pub fn with_http_client<S: Into<String>>(
+        http: reqwest::Client,
+        api_key: S,
+    ) -> Result<Self, Error> {
+        Self::from_async(crate::OpenAI::with_http_client(http, api_key)?)
+    }
+
+    pub fn from_env() -> Result<Self, Error> {
+        Self::from_async(crate::OpenAI::from_env()?)
+    }
+
+    pub fn azure_from_env() -> Result<Self, Error> {
+        Self::from_async(crate::OpenAI::azure_from_env()?)
+    }
+
+    /// The async builder; finish it with [`OpenAIBuilder::build_blocking`].
+    pub fn builder() -> OpenAIBuilder {
+        crate::OpenAI::builder()
+    }
+
+    pub fn base_url(&self) -> String {
+        self.inner.base_url()
+    }
+
+    /// The async client this one drives.
+    pub fn as_async(&self) -> &crate::OpenAI {
+        &self.inner
+    }
+
+    /// Access endpoints that return the HTTP metadata along with the body.
+    pub fn with_raw_response(&self) -> WithRawResponse<'_> {
+        WithRawResponse {
+            client: self,
+            opts: RequestOptions::default(),
+        }
+    }
+
+    blocking_methods! {
+        chat_completion(req: ChatCompletionRequest) -> ChatCompletionResponse;
+        chat_completion_with_options(req: ChatCompletionRequest, opts: RequestOptions) -> ChatCompletionResponse;
+        embeddings(req: EmbeddingsRequest) -> EmbeddingsResponse;
+        embeddings_with_options(req: EmbeddingsRequest, opts: RequestOptions) -> EmbeddingsResponse;
+        responses(req: ResponsesRequest) -> ResponsesResponse;
+        responses_with_options(req: ResponsesRequest, opts: RequestOptions) -> ResponsesResponse;
+        images_generate(req: ImageGenerationRequest) -> ImageGenerationResponse;
+        images_generate_with_options(req: ImageGenerationRequest, opts: RequestOptions) -> ImageGenerationResponse;
+        files_list() -> FileListResponse;
+        files_list_with_options(opts: RequestOptions) -> FileListResponse;
+        files_upload_bytes(filename: &str, bytes: Vec<u8>, purpose: &str) -> FileObject;
+        files_upload_bytes_with_options(filename: &str, bytes: Vec<u8>, purpose: &str, opts: RequestOptions) -> FileObject;
+        files_download(file_id: &str) -> Vec<u8>;
+        files_download_with_options(file_id: &str, opts: RequestOptions) -> Vec<u8>;
+        files_delete(file_id: &str) -> FileDeleteResponse;
+        files_delete_with_options(file_id: &str, opts: RequestOptions) -> FileDeleteResponse;
+        chat_completion_stream_text(req: ChatCompletionRequest) -> String;
+        chat_completion_stream_text_with_options(req: ChatCompletionRequest, opts: RequestOptions) -> String;
+        responses_stream_text(req: ResponsesRequest) -> String;
+        responses_stream_text_with_options(req: ResponsesRequest, opts: RequestOptions) -> String;
+    }
+
+    pub fn chat_completion_stream(
+        &self,
+        req: ChatCompletionRequest,
+    ) -> Result<StreamIter<ChatCompletionChunk>, Error> {
+        self.chat_completion_stream_with_options(req, RequestOptions::default())
+    }
+
+    pub fn chat_completion_stream_with_options(
+        &self,
+        req: ChatCompletionRequest,
+        opts: RequestOptions,
+    ) -> Result<StreamIter<ChatCompletionChunk>, Error> {
+        let fut = self.inner.chat_completion_stream_with_options(req, opts);
+        Ok(self.iter(self.rt.block_on(fut)?))
+    }
+
+    pub fn responses_stream(
+        &self,
+        req: ResponsesRequest,
+    ) -> Result<StreamIter<ResponseStreamEvent>, Error> {
+        self.responses_stream_with_options(req, RequestOptions::default())
+    }
+
+    pub fn responses_stream_with_options(
+        &self,
+        req: ResponsesRequest,
+        opts: RequestOptions,
+    ) -> Result<StreamIter<ResponseStreamEvent>, Error> {
+        let fut = self.inner.responses_stream_with_options(req, opts);
+        Ok(self.iter(self.rt.block_on(fut)?))
+    }
+
+    pub fn responses_stream_resume(
+        &self,
+        response_id: &str,
+        starting_after: Option<u64>,
+    ) -> Result<StreamIter<ResponseStreamEvent>, Error> {
+        self.responses_stream_resume_with_options(
+            response_id,
+            starting_after,
+            RequestOptions::default(),
+        )
+    }
+
+    pub fn responses_stream_resume_with_options(
+        &self,
+        response_id: &str,
+        starting_after: Option<u64>,
+        opts: RequestOptions,
+    ) -> Result<StreamIter<ResponseStreamEvent>, Error> {
+        let fut =
+            self.inner
+                .responses_stream_resume_with_options(response_id, starting_after, opts);
+        Ok(self.iter(self.rt.block_on(fut)?))
+    }
+
+    fn iter<T>(&self, stream: BoxStream<'static, Result<T, Error>>) -> StreamIter<T> {
+        StreamIter {
+            stream,
+            rt: self.rt.clone(),
+        }
+    }
+}
+
+/// Items of a streaming response, read by blocking on each one.
+pub struct StreamIter<T> {
+    stream: BoxStream<'static, Result<T, Error>>,
+    rt: Arc<Runtime>,
+}
+
+impl<T> std::fmt::Debug for StreamIter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamIter").finish_non_exhaustive()
+    }
+}
+
+impl<T> Iterator for StreamIter<T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rt.block_on(self.stream.next())
+    }
+}
+
+/// Blocking counterpart of [`crate::WithRawResponse`].
+#[derive(Debug)]
+pub struct WithRawResponse<'a> {
+    client: &'a OpenAI,
+    opts: RequestOptions,
+}
+
+impl WithRawResponse<'_> {
+    /// Apply per-call options to requests made through this handle.
+    pub fn options(mut self, opts: RequestOptions) -> Self {
+        self.opts = opts;
+        self
+    }
+
+    fn raw(&self) -> crate::WithRawResponse<'_> {
+        self.client
+            .inner
+            .with_raw_response()
+            .options(self.opts.clone())
+    }
+
+    fn block_on<F: std::future::Future>(&self, fut: F) -> F::Output {
+        self.client.rt.block_on(fut)
+    }
+
+    pub fn chat_completion(
+        &self,
+        req: ChatCompletionRequest,
+    ) -> Result<RawResponse<ChatCompletionResponse>, Error> {
+        self.block_on(self.raw().chat_completion(req))
+    }
+
+    pub fn embeddings(
+        &self,
+        req: EmbeddingsRequest,
+    ) -> Result<RawResponse<EmbeddingsResponse>, Error> {
+        self.block_on(self.raw().embeddings(req))
+    }
+
+    pub fn chat_completion_stream(
+        &self,
+        req: ChatCompletionRequest,
+    ) -> Result<RawResponse<StreamIter<ChatCompletionChunk>>, Error> {
+        let raw = self.raw();
+        let resp = self.block_on(raw.chat_completion_stream(req))?;
+        Ok(RawResponse {
+            data: self.client.iter(resp.data),
+            meta: resp.meta,
+        })
+    }
+
+    pub fn responses(
+        &self,
+        req: ResponsesRequest,
+    ) -> Result<RawResponse<ResponsesResponse>, Error> {
+        self.block_on(self.raw().responses(req))
+    }
+
+    pub fn responses_stream(
+        &self,
+        req: ResponsesRequest,
+    ) -> Result<RawResponse<StreamIter<ResponseStreamEvent>>, Error> {
+        let raw = self.raw();
+        let resp = self.block_on(raw.responses_stream(req))?;
+        Ok(RawResponse {
+            data: self.client.iter(resp.data),
+            meta: resp.meta,
+        })
+    }
+
+    pub fn responses_stream_resume(
+        &self,
+        response_id: &str,
+        starting_after: Option<u64>,
+    ) -> Result<RawResponse<StreamIter<ResponseStreamEvent>>, Error> {
+        let raw = self.raw();
+        let resp = self.block_on(raw.responses_stream_resume(response_id, starting_after))?;
+        Ok(RawResponse {
+            data: self.client.iter(resp.data),
+            meta: resp.meta,
+        })
+    }
+
+    pub fn images_generate(
+        &self,
+        req: ImageGenerationRequest,
+    ) -> Result<RawResponse<ImageGenerationResponse>, Error> {
+        self.block_on(self.raw().images_generate(req))
+    }
+
+    pub fn files_list(&self) -> Result<RawResponse<FileListResponse>, Error> {
+        self.block_on(self.raw().files_list())
+    }
+
+    pub fn files_upload_bytes(
+        &self,
+        filename: &str,
+        bytes: Vec<u8>,
+        purpose: &str,
+    ) -> Result<RawResponse<FileObject>, Error> {
+        self.block_on(self.raw().files_upload_bytes(filename, bytes, purpose))
+    }
+
+    pub fn files_download(&self, file_id: &str) -> Result<RawResponse<Vec<u8>>, Error> {
+        self.block_on(self.raw().files_download(file_id))
+    }
+
+    pub fn files_delete(&self, file_id: &str) -> Result<RawResponse<FileDeleteResponse>, Error> {
+        self.block_on(self.raw().files_delete(file_id))
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::transport::{transport_kind, HttpResponse};
+use crate::utils::Instant;
+
+/// State of one circuit of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Calls pass through; failures are counted.
+    Closed,
+    /// Calls fail fast with [`Error::CircuitOpen`].
+    Open,
+    /// The cool-down has passed and a probe call is allowed through.
+    HalfOpen,
+}
+
+/// A transition reported to the [`CircuitBreaker::on_state_change`] callback.
+#[derive(Debug, Clone)]
+pub struct StateChange {
+    pub path: String,
+    pub model: Option<String>,
+    pub from: BreakerState,
+    pub to: BreakerState,
+}
+
+type Listener = Arc<dyn Fn(&StateChange) + Send + Sync>;
+type Key = (String, Option<String>);
+
+/// Fails calls fast while an endpoint keeps failing, instead of letting each
+/// one spend its full retry budget.
+///
+/// Every endpoint path and model pair has its own circuit. A call that still
+/// fails after its retries with `429`, `5xx`, a timeout or a connection error
+/// counts as a failure; `failure_threshold` failures in a row open the
+/// circuit. After `open_for`, one probe call is let through: success closes
+/// the circuit, failure opens it again.
+///
+/// The breaker is cheap to clone; clones share state.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    open_for: Duration,
+    listener: Option<Listener>,
+    circuits: Arc<Mutex<HashMap<Key, Circuit>>>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_for: Duration::from_secs(30),
+            listener: None,
+            circuits: Default::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("failure_threshold", &self.failure_threshold)
+            .field("open_for", &self.open_for)
+            .field("listener", &self.listener.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+struct Circuit {
+    state: BreakerState,
+    failures: u32,
+    opened_at: Instant,
+    probing: bool,
+    // Bumped on every state change; a trial only counts in the state it was
+    // admitted in.
+    epoch: u64,
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consecutive failures that open a circuit.
+    pub fn failure_threshold(mut self, failures: u32) -> Self {
+        self.failure_threshold = failures.max(1);
+        self
+    }
+
+    /// How long an open circuit fails fast before letting a probe through.
+    pub fn open_for(mut self, duration: Duration) -> Self {
+        self.open_for = duration;
+        self
+    }
+
+    /// Called after every state transition, outside the breaker's lock.
+    pub fn on_state_change<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&StateChange) + Send + Sync + 'static,
+    {
+        self.listener = Some(Arc::new(f));
+        self
+    }
+
+    /// Current state of the circuit for `path` and `model`.
+    pub fn state(&self, path: &str, model: Option<&str>) -> BreakerState {
+        let circuits = self.circuits.lock().expect("breaker lock");
+        circuits
+            .get(&(path.to_string(), model.map(str::to_string)))
+            .map_or(BreakerState::Closed, |c| c.state)
+    }
+
+    // Admit a call, or fail fast when its circuit is open.
+    pub(crate) fn enter(&self, path: &str, model: Option<&str>) -> Result<Trial, Error> {
+        let key = (path.to_string(), model.map(str::to_string));
+        let mut change = None;
+        let result = {
+            let mut circuits = self.circuits.lock().expect("breaker lock");
+            let c = circuits.entry(key.clone()).or_insert(Circuit {
+                state: BreakerState::Closed,
+                failures: 0,
+                opened_at: Instant::now(),
+                probing: false,
+                epoch: 0,
+            });
+            if c.state == BreakerState::Open && c.opened_at.elapsed() >= self.open_for {
+                c.state = BreakerState::HalfOpen;
+                c.epoch += 1;
+                change = Some((BreakerState::Open, BreakerState::HalfOpen));
+            }
+            match c.state {
+                BreakerState::Closed => Ok((false, c.epoch)),
+                BreakerState::HalfOpen if !c.probing => {
+                    c.probing = true;
+                    Ok((true, c.epoch))
+                }
+                _ => Err(Error::CircuitOpen {
+                    path: key.0.clone(),
+                    model: key.1.clone(),
+                    retry_in: self.open_for.saturating_sub(c.opened_at.elapsed()),
+                }),
+            }
+        };
+        self.notify(&key, change);
+        result.map(|(probe, epoch)| Trial {
+            breaker: self.clone(),
+            key: Some(key),
+            probe,
+            epoch,
+        })
+    }
+
+    fn finish(&self, key: Key, probe: bool, epoch: u64, failed: Option<bool>) {
+        let change = {
+            let mut circuits = self.circuits.lock().expect("breaker lock");
+            let Some(c) = circuits.get_mut(&key) else {
+                return;
+            };
+            if probe {
+                c.probing = false;
+            }
+            // A call admitted before the circuit last changed state, e.g. one
+            // still running when it opened, must not close it behind the
+            // probe's back.
+            if c.epoch != epoch {
+                return;
+            }
+            let from = c.state;
+            match failed {
+                // A probe abandoned before it finished says nothing.
+                None => {}
+                Some(false) => {
+                    c.failures = 0;
+                    c.state = BreakerState::Closed;
+                }
+                Some(true) => {
+                    c.failures += 1;
+                    if from == BreakerState::HalfOpen || c.failures >= self.failure_threshold {
+                        c.state = BreakerState::Open;
+                        c.opened_at = Instant::now();
+                    }
+                }
+            }
+            if from != c.state {
+                c.epoch += 1;
+            }
+            (from != c.state).then_some((from, c.state))
+        };
+        self.notify(&key, change);
+    }
+
+    fn notify(&self, key: &Key, change: Option<(BreakerState, BreakerState)>) {
+        if let (Some(listener), Some((from, to))) = (&self.listener, change) {
+            listener(&StateChange {
+                path: key.0.clone(),
+                model: key.1.clone(),
+                from,
+                to,
+            });
+        }
+    }
+}
+
+/// A call admitted by [`CircuitBreaker::enter`]; report its outcome with
+/// [`Trial::record`].
+pub(crate) struct Trial {
+    breaker: CircuitBreaker,
+    key: Option<Key>,
+    probe: bool,
+    epoch: u64,
+}
+
+impl Trial {
+    pub(crate) fn record(self, res: &Result<HttpResponse, Error>) {
+        let failed = match res {
+            Ok(resp) => {
+                let status = resp.status().as_u16();
+                Some(status == 429 || status >= 500)
+            }
+            // Every transport failure counts against the endpoint. Other
+            // errors (credentials, middleware) say nothing about it, so a
+            // probe ending with one leaves the circuit half-open.
+            Err(e) => transport_kind(e).map(|_| true),
+        };
+        self.complete(failed);
+    }
+
+    fn complete(mut self, failed: Option<bool>) {
+        if let Some(key) = self.key.take() {
+            self.breaker.finish(key, self.probe, self.epoch, failed);
+        }
+    }
+}
+
+impl Drop for Trial {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.breaker.finish(key, self.probe, self.epoch, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{BreakerState, CircuitBreaker};
+    use crate::error::Error;
+    use crate::transport::{TransportError, TransportErrorKind};
+
+    #[test]
+    fn opens_probes_and_closes() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let b = CircuitBreaker::new()
+            .failure_threshold(2)
+            .open_for(Duration::ZERO)
+            .on_state_change(move |c| log.lock().unwrap().push(c.to));
+        let fail = || {
+            b.enter("/v1/chat/completions", Some("m"))
+                .unwrap()
+                .complete(Some(true))
+        };
+        fail();
+        assert_eq!(
+            b.state("/v1/chat/completions", Some("m")),
+            BreakerState::Closed
+        );
+        fail();
+        assert_eq!(
+            b.state("/v1/chat/completions", Some("m")),
+            BreakerState::Open
+        );
+
+        // open_for is zero, so the next call is a probe; a second concurrent
+        // call fails fast while it runs.
+        let probe = b.enter("/v1/chat/completions", Some("m")).unwrap();
+        assert!(b.enter("/v1/chat/completions", Some("m")).is_err());
+        assert!(b.enter("/v1/chat/completions", Some("other")).is_ok());
+        probe.complete(Some(false));
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                BreakerState::Open,
+                BreakerState::HalfOpen,
+                BreakerState::Closed
+            ]
+        );
+    }
+
+    #[test]
+    fn late_success_cannot_close_an_open_circuit() {
+        let b = CircuitBreaker::new()
+            .failure_threshold(1)
+            .open_for(Duration::from_secs(60));
+        let path = "/v1/chat/completions";
+        let slow = b.enter(path, None).unwrap();
+        b.enter(path, None).unwrap().complete(Some(true));
+        assert_eq!(b.state(path, None), BreakerState::Open);
+
+        // Admitted while closed, it finishes after the circuit opened.
+        slow.complete(Some(false));
+        assert_eq!(b.state(path, None), BreakerState::Open);
+    }
+
+    #[test]
+    fn failed_probe_cannot_close_the_circuit() {
+        let b = CircuitBreaker::new()
+            .failure_threshold(1)
+            .open_for(Duration::ZERO);
+        let path = "/v1/embeddings";
+        b.enter(path, None).unwrap().complete(Some(true));
+        assert_eq!(b.state(path, None), BreakerState::Open);
+
+        // A probe failing before it reaches the endpoint gives no signal.
+        let probe = b.enter(path, None).unwrap();
+        probe.record(&Err(Error::Config("no credentials".into())));
+        assert_eq!(b.state(path, None), BreakerState::HalfOpen);
+
+        // A probe whose body read fails reopens the circuit.
+        let probe = b.enter(path, None).unwrap();
+        let err = TransportError::new(TransportErrorKind::Body, "reset");
+        probe.record(&Err(Error::Transport(err)));
+        assert_eq!(b.state(path, None), BreakerState::Open);
+    }
+}
@@ -1,30 +1,62 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_stream::try_stream;
 use futures_util::{StreamExt, TryStreamExt};
-use reqwest::{header, Client as HttpClient, StatusCode, Url};
+use http::{header, Method, StatusCode};
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::Url;
+use zeroize::Zeroizing;
 
-use crate::error::{ApiError, ApiErrorEnvelope, Error};
+use crate::azure::{AzureAuth, AzureConfig};
+use crate::breaker::CircuitBreaker;
+use crate::credentials::{Credential, CredentialCache, CredentialProvider, StaticCredential};
+use crate::error::{ApiError, ApiErrorBody, ApiErrorEnvelope, Error, StreamTimeoutKind};
+use crate::meta::{request_id, RawResponse, ResponseMeta};
+use crate::metrics::MetricsSink;
+use crate::middleware::{Middleware, Next};
+use crate::multipart::Multipart;
+use crate::options::RequestOptions;
+use crate::rate_limit::{estimate_tokens, Permit, RateLimiter};
+use crate::retry::{RetryContext, RetryPolicy};
+use crate::secret::SecretString;
+use crate::sse::SseDecoder;
+use crate::telemetry::{CallTelemetry, TokenUsage};
+#[cfg(feature = "reqwest")]
+use crate::transport::ReqwestTransport;
+use crate::transport::{transport_kind, ByteStream, HttpRequest, HttpResponse, HttpTransport};
 use crate::types::chat::{ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse};
 use crate::types::embeddings::{EmbeddingsRequest, EmbeddingsResponse};
 use crate::types::files::{FileDeleteResponse, FileListResponse, FileObject};
 use crate::types::images::{ImageGenerationRequest, ImageGenerationResponse};
-use crate::types::responses::{ResponseStreamEvent, ResponsesRequest, ResponsesResponse};
-use crate::utils::sleep;
+use crate::types::responses::{
+    ResponseOutcome, ResponseStreamEvent, ResponsesRequest, ResponsesResponse,
+};
 use crate::utils::BoxStream;
+use crate::utils::{sleep, timeout, Instant};
 
 const DEFAULT_BASE_URL: &str = "https://api.openai.com";
+const DEFAULT_AZURE_API_VERSION: &str = "2024-10-21";
 
 #[derive(Clone)]
 pub struct OpenAI {
-    http: HttpClient,
+    transport: Arc<dyn HttpTransport>,
     base_url: Url,
-    api_key: String,
+    timeout: Option<Duration>,
+    stream_first_byte_timeout: Option<Duration>,
+    stream_idle_timeout: Option<Duration>,
+    user_agent: Option<header::HeaderValue>,
+    default_query: Vec<(String, String)>,
+    credentials: CredentialCache,
     org: Option<String>,
     project: Option<String>,
-    max_retries: u32,
-    retry_base_delay_ms: u64,
+    azure: Option<AzureConfig>,
+    middleware: Arc<Vec<Arc<dyn Middleware>>>,
+    retry: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    breaker: Option<CircuitBreaker>,
+    metrics: Option<Arc<dyn MetricsSink>>,
 }
 
 impl std::fmt::Debug for OpenAI {
@@ -33,6 +65,7 @@ impl std::fmt::Debug for OpenAI {
             .field("base_url", &self.base_url)
             .field("org", &self.org)
             .field("project", &self.project)
+            .field("azure", &self.azure)
             .finish_non_exhaustive()
     }
 }
@@ -46,7 +79,11 @@ impl OpenAI {
         Self::builder().api_key(api_key.into()).build()
     }
 
-    pub fn with_http_client<S: Into<String>>(http: HttpClient, api_key: S) -> Result<Self, Error> {
+    #[cfg(feature = "reqwest")]
+    pub fn with_http_client<S: Into<String>>(
+        http: reqwest::Client,
+        api_key: S,
+    ) -> Result<Self, Error> {
         Self::builder()
             .http_client(http)
             .api_key(api_key.into())
@@ -68,50 +105,178 @@ impl OpenAI {
         b.build()
     }
 
+    /// Build an Azure OpenAI client from the environment.
+    ///
+    /// Reads `AZURE_OPENAI_ENDPOINT` and `OPENAI_API_VERSION`, plus either
+    /// `AZURE_OPENAI_API_KEY` or an Entra ID token in `AZURE_OPENAI_AD_TOKEN`.
+    pub fn azure_from_env() -> Result<Self, Error> {
+        let endpoint = std::env::var("AZURE_OPENAI_ENDPOINT")
+            .map_err(|_| Error::Config("AZURE_OPENAI_ENDPOINT is not set".into()))?;
+        let api_version = std::env::var("OPENAI_API_VERSION")
+            .unwrap_or_else(|_| DEFAULT_AZURE_API_VERSION.to_string());
+        let (key, auth) = if let Ok(k) = std::env::var("AZURE_OPENAI_API_KEY") {
+            (k, AzureAuth::ApiKey)
+        } else if let Ok(t) = std::env::var("AZURE_OPENAI_AD_TOKEN") {
+            (t, AzureAuth::EntraId)
+        } else {
+            return Err(Error::MissingApiKey);
+        };
+        Self::builder()
+            .api_key(key)
+            .base_url(endpoint)
+            .azure(AzureConfig::new(api_version).auth(auth))
+            .build()
+    }
+
     pub fn builder() -> OpenAIBuilder {
         OpenAIBuilder::default()
     }
 
+    /// Access endpoints that return the HTTP metadata (request id, rate-limit
+    /// headers, processing time) along with the typed body.
+    pub fn with_raw_response(&self) -> WithRawResponse<'_> {
+        WithRawResponse {
+            client: self,
+            opts: RequestOptions::default(),
+        }
+    }
+
     pub async fn chat_completion(
         &self,
         req: ChatCompletionRequest,
     ) -> Result<ChatCompletionResponse, Error> {
-        self.post_json("/v1/chat/completions", &req).await
+        self.chat_completion_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    pub async fn chat_completion_with_options(
+        &self,
+        req: ChatCompletionRequest,
+        opts: RequestOptions,
+    ) -> Result<ChatCompletionResponse, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.chat_completion(req).await.map(RawResponse::into_data)
     }
 
     pub async fn embeddings(&self, req: EmbeddingsRequest) -> Result<EmbeddingsResponse, Error> {
-        self.post_json("/v1/embeddings", &req).await
+        self.embeddings_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    pub async fn embeddings_with_options(
+        &self,
+        req: EmbeddingsRequest,
+        opts: RequestOptions,
+    ) -> Result<EmbeddingsResponse, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.embeddings(req).await.map(RawResponse::into_data)
     }
 
     pub async fn chat_completion_stream(
         &self,
-        mut req: ChatCompletionRequest,
+        req: ChatCompletionRequest,
     ) -> Result<BoxStream<'static, Result<ChatCompletionChunk, Error>>, Error> {
-        req.stream = Some(true);
-        self.post_sse("/v1/chat/completions", &req).await
+        self.chat_completion_stream_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    pub async fn chat_completion_stream_with_options(
+        &self,
+        req: ChatCompletionRequest,
+        opts: RequestOptions,
+    ) -> Result<BoxStream<'static, Result<ChatCompletionChunk, Error>>, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.chat_completion_stream(req)
+            .await
+            .map(RawResponse::into_data)
     }
 
     pub async fn responses(&self, req: ResponsesRequest) -> Result<ResponsesResponse, Error> {
-        self.post_json("/v1/responses", &req).await
+        self.responses_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    pub async fn responses_with_options(
+        &self,
+        req: ResponsesRequest,
+        opts: RequestOptions,
+    ) -> Result<ResponsesResponse, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.responses(req).await.map(RawResponse::into_data)
     }
 
     pub async fn responses_stream(
         &self,
-        mut req: ResponsesRequest,
+        req: ResponsesRequest,
     ) -> Result<BoxStream<'static, Result<ResponseStreamEvent, Error>>, Error> {
-        req.stream = Some(true);
-        self.post_sse("/v1/responses", &req).await
+        self.responses_stream_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    pub async fn responses_stream_with_options(
+        &self,
+        req: ResponsesRequest,
+        opts: RequestOptions,
+    ) -> Result<BoxStream<'static, Result<ResponseStreamEvent, Error>>, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.responses_stream(req).await.map(RawResponse::into_data)
+    }
+
+    /// Stream the events of a stored or background response, from the
+    /// start or after event `starting_after`.
+    pub async fn responses_stream_resume(
+        &self,
+        response_id: &str,
+        starting_after: Option<u64>,
+    ) -> Result<BoxStream<'static, Result<ResponseStreamEvent, Error>>, Error> {
+        self.responses_stream_resume_with_options(
+            response_id,
+            starting_after,
+            RequestOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn responses_stream_resume_with_options(
+        &self,
+        response_id: &str,
+        starting_after: Option<u64>,
+        opts: RequestOptions,
+    ) -> Result<BoxStream<'static, Result<ResponseStreamEvent, Error>>, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.responses_stream_resume(response_id, starting_after)
+            .await
+            .map(RawResponse::into_data)
     }
 
     pub async fn images_generate(
         &self,
         req: ImageGenerationRequest,
     ) -> Result<ImageGenerationResponse, Error> {
-        self.post_json("/v1/images/generations", &req).await
+        self.images_generate_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    pub async fn images_generate_with_options(
+        &self,
+        req: ImageGenerationRequest,
+        opts: RequestOptions,
+    ) -> Result<ImageGenerationResponse, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.images_generate(req).await.map(RawResponse::into_data)
     }
 
     pub async fn files_list(&self) -> Result<FileListResponse, Error> {
-        self.get_json("/v1/files").await
+        self.files_list_with_options(RequestOptions::default())
+            .await
+    }
+
+    pub async fn files_list_with_options(
+        &self,
+        opts: RequestOptions,
+    ) -> Result<FileListResponse, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.files_list().await.map(RawResponse::into_data)
     }
 
     pub async fn files_upload_bytes(
@@ -120,95 +285,67 @@ impl OpenAI {
         bytes: Vec<u8>,
         purpose: &str,
     ) -> Result<FileObject, Error> {
-        let url = self.base_url.join("/v1/files").expect("valid path");
-        let form = reqwest::multipart::Form::new()
-            .text("purpose", purpose.to_string())
-            .part(
-                "file",
-                reqwest::multipart::Part::bytes(bytes).file_name(filename.to_string()),
-            );
-
-        let mut req = self
-            .http
-            .post(url)
-            .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
-            .multipart(form);
-        if let Some(org) = &self.org {
-            req = req.header("OpenAI-Organization", org);
-        }
-        if let Some(project) = &self.project {
-            req = req.header("OpenAI-Project", project);
-        }
-        let resp = self
-            .execute_with_retry(|| req.try_clone().expect("req clone"), false)
-            .await?;
-        let status = resp.status();
-        if status.is_success() {
-            Ok(resp.json::<FileObject>().await?)
-        } else {
-            Self::map_api_error(status, resp).await
-        }
+        self.files_upload_bytes_with_options(filename, bytes, purpose, RequestOptions::default())
+            .await
+    }
+
+    pub async fn files_upload_bytes_with_options(
+        &self,
+        filename: &str,
+        bytes: Vec<u8>,
+        purpose: &str,
+        opts: RequestOptions,
+    ) -> Result<FileObject, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.files_upload_bytes(filename, bytes, purpose)
+            .await
+            .map(RawResponse::into_data)
     }
 
     pub async fn files_download(&self, file_id: &str) -> Result<Vec<u8>, Error> {
-        let mk = || {
-            let url = self
-                .base_url
-                .join(&format!("/v1/files/{}/content", file_id))
-                .expect("valid path");
-            let mut req = self
-                .http
-                .get(url)
-                .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key));
-            if let Some(org) = &self.org {
-                req = req.header("OpenAI-Organization", org);
-            }
-            if let Some(project) = &self.project {
-                req = req.header("OpenAI-Project", project);
-            }
-            req
-        };
-        let resp = self.execute_with_retry(mk, false).await?;
-        let status = resp.status();
-        if status.is_success() {
-            Ok(resp.bytes().await?.to_vec())
-        } else {
-            Self::map_api_error(status, resp).await
-        }
+        self.files_download_with_options(file_id, RequestOptions::default())
+            .await
+    }
+
+    pub async fn files_download_with_options(
+        &self,
+        file_id: &str,
+        opts: RequestOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.files_download(file_id)
+            .await
+            .map(RawResponse::into_data)
     }
 
     pub async fn files_delete(&self, file_id: &str) -> Result<FileDeleteResponse, Error> {
-        let mk = || {
-            let url = self
-                .base_url
-                .join(&format!("/v1/files/{}", file_id))
-                .expect("valid path");
-            let mut req = self
-                .http
-                .delete(url)
-                .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key));
-            if let Some(org) = &self.org {
-                req = req.header("OpenAI-Organization", org);
-            }
-            if let Some(project) = &self.project {
-                req = req.header("OpenAI-Project", project);
-            }
-            req
-        };
-        let resp = self.execute_with_retry(mk, false).await?;
-        let status = resp.status();
-        if status.is_success() {
-            Ok(resp.json::<FileDeleteResponse>().await?)
-        } else {
-            Self::map_api_error(status, resp).await
-        }
+        self.files_delete_with_options(file_id, RequestOptions::default())
+            .await
+    }
+
+    pub async fn files_delete_with_options(
+        &self,
+        file_id: &str,
+        opts: RequestOptions,
+    ) -> Result<FileDeleteResponse, Error> {
+        let raw = self.with_raw_response().options(opts);
+        raw.files_delete(file_id).await.map(RawResponse::into_data)
     }
 
     pub async fn chat_completion_stream_text(
         &self,
         req: ChatCompletionRequest,
     ) -> Result<String, Error> {
-        let mut stream = self.chat_completion_stream(req).await?;
+        self.chat_completion_stream_text_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    pub async fn chat_completion_stream_text_with_options(
+        &self,
+        req: ChatCompletionRequest,
+        opts: RequestOptions,
+    ) -> Result<String, Error> {
+        let mut stream = self.chat_completion_stream_with_options(req, opts).await?;
         let mut out = String::new();
         while let Some(chunk) = stream.try_next().await? {
             if let Some(text) = chunk
@@ -223,11 +360,29 @@ impl OpenAI {
     }
 
     pub async fn responses_stream_text(&self, req: ResponsesRequest) -> Result<String, Error> {
-        let mut stream = self.responses_stream(req).await?;
+        self.responses_stream_text_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    pub async fn responses_stream_text_with_options(
+        &self,
+        req: ResponsesRequest,
+        opts: RequestOptions,
+    ) -> Result<String, Error> {
+        let mut stream = self.responses_stream_with_options(req, opts).await?;
 
         let mut out = String::new();
         while let Some(ev) = stream.next().await {
             let ev = ev?;
+            if let Some(ResponseOutcome::Failed { error }) = ev.outcome() {
+                let error = error.unwrap_or_else(|| ApiErrorBody {
+                    message: "response failed".to_string(),
+                    type_: None,
+                    param: None,
+                    code: None,
+                });
+                return Err(ApiError::from(error).into());
+            }
             if let Some(text) = ev.clone().output_text.as_deref() {
                 out.push_str(text);
             } else if let Some(d) = ev
@@ -247,251 +402,798 @@ impl OpenAI {
         &self,
         path: &str,
         body: &TReq,
-    ) -> Result<TResp, Error> {
-        let mk = || {
-            let url = self.base_url.join(path).expect("valid path");
-            let mut req = self
-                .http
-                .post(url)
-                .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
-                .json(body);
-            if let Some(org) = &self.org {
-                req = req.header("OpenAI-Organization", org);
-            }
-            if let Some(project) = &self.project {
-                req = req.header("OpenAI-Project", project);
+        idempotent: bool,
+        opts: &RequestOptions,
+    ) -> Result<RawResponse<TResp>, Error> {
+        let (body, model) = self.json_body(body, opts)?;
+        let url = self.endpoint_url(path, model.as_deref())?;
+        let req = json_request(url, &body)?;
+
+        let call = self.telemetry("POST", path, model.as_deref());
+        let permit = self.reserve(model.as_deref(), &body).await;
+        let resp = match self
+            .execute(&call, path, model.as_deref(), req, idempotent, opts)
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => {
+                self.settle(permit, None, Some(0));
+                return Err(e);
             }
-            req
         };
-
-        let resp = self.execute_with_retry(mk, false).await?;
         let status = resp.status();
-        if status.is_success() {
-            Ok(resp.json::<TResp>().await?)
-        } else {
-            Self::map_api_error(status, resp).await
+        let meta = ResponseMeta::from_response(&resp);
+        if !status.is_success() {
+            self.settle(permit, Some(&meta), Some(0));
+            return Self::map_api_error(&call, status, resp).await;
         }
+        let bytes = resp.bytes().await?;
+        let usage = TokenUsage::from_body(&bytes);
+        if let Some(usage) = usage {
+            call.usage(usage);
+        }
+        call.response_model(&bytes);
+        self.settle(permit, Some(&meta), usage.map(|u| u.total));
+        Ok(RawResponse {
+            data: serde_json::from_slice(&bytes)?,
+            meta,
+        })
     }
 
-    async fn get_json<TResp: DeserializeOwned>(&self, path: &str) -> Result<TResp, Error> {
-        let mk = || {
-            let url = self.base_url.join(path).expect("valid path");
-            let mut req = self
-                .http
-                .get(url)
-                .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key));
-            if let Some(org) = &self.org {
-                req = req.header("OpenAI-Organization", org);
-            }
-            if let Some(project) = &self.project {
-                req = req.header("OpenAI-Project", project);
-            }
-            req
-        };
-        let resp = self.execute_with_retry(mk, false).await?;
-        let status = resp.status();
-        if status.is_success() {
-            Ok(resp.json::<TResp>().await?)
-        } else {
-            Self::map_api_error(status, resp).await
-        }
+    async fn get_json<TResp: DeserializeOwned>(
+        &self,
+        path: &str,
+        opts: &RequestOptions,
+    ) -> Result<RawResponse<TResp>, Error> {
+        let req = HttpRequest::new(Method::GET, self.endpoint_url(path, None)?);
+        let call = self.telemetry("GET", path, None);
+        let resp = self.execute(&call, path, None, req, true, opts).await?;
+        Self::read_json(&call, resp).await
     }
 
     async fn post_sse<TReq: serde::Serialize, TEvent: DeserializeOwned + Send + 'static>(
         &self,
         path: &str,
         body: &TReq,
-    ) -> Result<BoxStream<'static, Result<TEvent, Error>>, Error> {
-        let mk = || {
-            let url = self.base_url.join(path).expect("valid path");
-            let mut req = self
-                .http
-                .post(url)
-                .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
-                .header(header::ACCEPT, "text/event-stream")
-                .json(body);
-            if let Some(org) = &self.org {
-                req = req.header("OpenAI-Organization", org);
-            }
-            if let Some(project) = &self.project {
-                req = req.header("OpenAI-Project", project);
+        opts: &RequestOptions,
+    ) -> Result<RawResponse<BoxStream<'static, Result<TEvent, Error>>>, Error> {
+        let (body, model) = self.json_body(body, opts)?;
+        let url = self.endpoint_url(path, model.as_deref())?;
+        let mut req = json_request(url, &body)?;
+        accept_event_stream(&mut req);
+
+        // Streams keep their token estimate; usage arrives after the budget
+        // decision matters.
+        let call = self.telemetry("POST", path, model.as_deref());
+        let permit = self.reserve(model.as_deref(), &body).await;
+        let resp = match self
+            .execute_stream(&call, path, model.as_deref(), req, opts)
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => {
+                self.settle(permit, None, Some(0));
+                return Err(e);
             }
-            req
         };
+        let status = resp.status();
+        let meta = ResponseMeta::from_response(&resp);
+        if !status.is_success() {
+            self.settle(permit, Some(&meta), Some(0));
+            return Self::map_api_error(&call, status, resp).await;
+        }
+        self.settle(permit, Some(&meta), None);
+        Ok(RawResponse {
+            data: Self::sse_json_stream::<TEvent>(resp, call),
+            meta,
+        })
+    }
 
-        let resp = self.execute_with_retry(mk, true).await?;
+    // Open an event stream with a GET, e.g. to resume a response.
+    async fn get_sse<TEvent: DeserializeOwned + Send + 'static>(
+        &self,
+        route: &str,
+        url: Url,
+        opts: &RequestOptions,
+    ) -> Result<RawResponse<BoxStream<'static, Result<TEvent, Error>>>, Error> {
+        let mut req = HttpRequest::new(Method::GET, url);
+        accept_event_stream(&mut req);
+        let call = self.telemetry("GET", route, None);
+        let resp = self.execute_stream(&call, route, None, req, opts).await?;
         let status = resp.status();
+        let meta = ResponseMeta::from_response(&resp);
         if !status.is_success() {
-            return Self::map_api_error(status, resp).await;
+            return Self::map_api_error(&call, status, resp).await;
+        }
+        Ok(RawResponse {
+            data: Self::sse_json_stream::<TEvent>(resp, call),
+            meta,
+        })
+    }
+
+    // Execute a streaming request under the stream timeouts of `opts` or the
+    // client. The first-byte timeout covers the whole call, retries
+    // included, up to the first body chunk; the idle timeout covers each
+    // gap between chunks after that.
+    async fn execute_stream(
+        &self,
+        call: &CallTelemetry,
+        path: &str,
+        model: Option<&str>,
+        req: HttpRequest,
+        opts: &RequestOptions,
+    ) -> Result<HttpResponse, Error> {
+        let first_byte = opts
+            .stream_first_byte_timeout
+            .or(self.stream_first_byte_timeout);
+        let idle = opts.stream_idle_timeout.or(self.stream_idle_timeout);
+        let started = Instant::now();
+        let exec = self.execute(call, path, model, req, true, opts);
+        let resp = match first_byte {
+            Some(limit) => match timeout(limit, exec).await {
+                Some(res) => res?,
+                None => {
+                    let err = Error::StreamTimeout {
+                        kind: StreamTimeoutKind::FirstByte,
+                        after: limit,
+                    };
+                    call.error(&err);
+                    return Err(err);
+                }
+            },
+            None => exec.await?,
+        };
+        let first_byte = first_byte.map(|limit| (limit.saturating_sub(started.elapsed()), limit));
+        Ok(resp.map_body(|body| watch_body(body, first_byte, idle)))
+    }
+
+    // Wait for rate-limiter budget for a call to `model`, if a limiter is set.
+    async fn reserve(&self, model: Option<&str>, body: &serde_json::Value) -> Option<Permit> {
+        let (limiter, model) = (self.rate_limiter.as_ref()?, model?);
+        Some(limiter.acquire(model, estimate_tokens(body)).await)
+    }
+
+    // Feed the outcome of a call back into the rate limiter: the server's
+    // remaining budget and the tokens actually used, when known.
+    fn settle(&self, permit: Option<Permit>, meta: Option<&ResponseMeta>, used: Option<u64>) {
+        if let (Some(limiter), Some(permit)) = (&self.rate_limiter, permit) {
+            limiter.settle(&permit, meta.map(|m| &m.rate_limit), used);
+        }
+    }
+
+    // Resolve an endpoint path such as `/v1/chat/completions` under the path
+    // of the base URL, switching to deployment URLs when running in Azure
+    // mode. A base path that already ends in `/v1` supplies that segment.
+    fn endpoint_url(&self, path: &str, model: Option<&str>) -> Result<Url, Error> {
+        let path = match &self.azure {
+            Some(azure) => azure.path_for(path, model),
+            None => path.to_string(),
+        };
+        let prefix = self.base_url.path().trim_end_matches('/');
+        let path = match path.strip_prefix("/v1") {
+            Some(rest) if prefix.ends_with("/v1") && rest.starts_with('/') => rest,
+            _ => &path,
+        };
+        let mut url = self.base_url.clone();
+        url.set_path(&format!("{}{}", prefix, path));
+        url.set_fragment(None);
+        if let Some(azure) = &self.azure {
+            url.query_pairs_mut()
+                .append_pair("api-version", azure.api_version());
+        }
+        Ok(url)
+    }
+
+    // Add credentials, scoping headers and per-call options to a request.
+    // Extra headers from the options are applied last and win.
+    fn finalize(
+        &self,
+        mut req: HttpRequest,
+        cred: &Credential,
+        opts: &RequestOptions,
+        idempotency_key: &header::HeaderValue,
+        extra_headers: &header::HeaderMap,
+    ) -> Result<HttpRequest, Error> {
+        let value = |name: &str, v: &str| {
+            header::HeaderValue::from_str(v).map_err(|_| Error::InvalidHeader(name.to_string()))
+        };
+        let secret = |name: &str, v: &str| {
+            let mut v = value(name, v)?;
+            v.set_sensitive(true);
+            Ok::<_, Error>(v)
+        };
+        match self.azure.as_ref().map(AzureConfig::auth_mode) {
+            Some(AzureAuth::ApiKey) => {
+                let key = secret("api-key", cred.token.expose_secret())?;
+                req.headers.insert("api-key", key);
+            }
+            _ => {
+                let bearer = Zeroizing::new(format!("Bearer {}", cred.token.expose_secret()));
+                let bearer = secret("Authorization", &bearer)?;
+                req.headers.insert(header::AUTHORIZATION, bearer);
+            }
+        }
+        if let Some(org) = opts.org.as_ref().or(self.org.as_ref()) {
+            let org = value("OpenAI-Organization", org)?;
+            req.headers.insert("OpenAI-Organization", org);
+        }
+        if let Some(project) = opts.project.as_ref().or(self.project.as_ref()) {
+            let project = value("OpenAI-Project", project)?;
+            req.headers.insert("OpenAI-Project", project);
         }
-        let res = Self::sse_json_stream::<TEvent>(resp);
-        Ok(res)
+        if let Some(ua) = &self.user_agent {
+            req.headers.insert(header::USER_AGENT, ua.clone());
+        }
+        req.timeout = opts.timeout.or(self.timeout);
+        let defaults = self
+            .default_query
+            .iter()
+            .filter(|(name, _)| opts.extra_query.iter().all(|(n, _)| n != name));
+        let query: Vec<_> = defaults.chain(&opts.extra_query).collect();
+        if !query.is_empty() {
+            req.url.query_pairs_mut().extend_pairs(query);
+        }
+        if req.method == Method::POST {
+            req.headers
+                .insert("Idempotency-Key", idempotency_key.clone());
+        }
+        for (name, value) in extra_headers {
+            req.headers.insert(name, value.clone());
+        }
+        Ok(req)
+    }
+
+    // Serialize a request body once, returning it with the model it targets.
+    // Extra body fields from the options are merged in, and since Azure
+    // identifies the model by deployment, the model is rewritten there.
+    fn json_body<T: serde::Serialize>(
+        &self,
+        body: &T,
+        opts: &RequestOptions,
+    ) -> Result<(serde_json::Value, Option<String>), Error> {
+        let mut body = serde_json::to_value(body)?;
+        if let Some(map) = body.as_object_mut() {
+            map.extend(opts.extra_body.clone());
+        }
+        let model = body
+            .get("model")
+            .and_then(|m| m.as_str())
+            .map(str::to_string);
+        if let (Some(azure), Some(m)) = (&self.azure, &model) {
+            body["model"] = serde_json::Value::String(azure.deployment_for(m).to_string());
+        }
+        Ok((body, model))
     }
 
     fn sse_json_stream<T: DeserializeOwned + Send + 'static>(
-        resp: reqwest::Response,
+        resp: HttpResponse,
+        mut call: CallTelemetry,
     ) -> BoxStream<'static, Result<T, Error>> {
+        let request_id = request_id(resp.headers());
         let stream = try_stream! {
-            let mut buf: Vec<u8> = Vec::new();
-            let mut byte_stream = resp.bytes_stream();
-            while let Some(chunk) = futures_util::StreamExt::next(&mut byte_stream).await {
-                let chunk = chunk?;
-                buf.extend_from_slice(&chunk);
-
-                let mut start = 0usize;
-                for i in 0..buf.len() {
-                    if buf[i] == b'\n' {
-                        let mut line = &buf[start..i];
-                        start = i + 1;
-                        if !line.is_empty() && line[line.len()-1] == b'\r' {
-                            line = &line[..line.len()-1];
-                        }
-                        if line.is_empty() { continue; }
-                        if line[0] == b':' { continue; }
-                        if let Some(rest) = line.strip_prefix(b"data: ") {
-                            if rest == b"[DONE]" { return; }
-                            let text = String::from_utf8(rest.to_vec()).unwrap_or_default();
-                            let val: T = serde_json::from_str(&text)?;
-                            yield val;
-                        }
+            let mut decoder = SseDecoder::new();
+            let mut byte_stream = resp.into_body();
+            while let Some(chunk) = byte_stream.next().await {
+                for event in decoder.feed(&chunk?) {
+                    if event.data == "[DONE]" {
+                        return;
                     }
+                    call.stream_data(event.data.as_bytes());
+                    let value: serde_json::Value = serde_json::from_str(&event.data)?;
+                    if let Some(err) = stream_error(&event.event, &value, &request_id) {
+                        call.error(&err);
+                        Err::<(), _>(err)?;
+                    }
+                    let val: T = serde_json::from_value(value)?;
+                    yield val;
                 }
-                if start > 0 { buf.drain(0..start); }
             }
         };
         Box::pin(stream)
     }
 
-    #[cfg(test)]
-    fn sse_extract_data_lines(text: &str) -> Vec<String> {
-        text.lines()
-            .filter_map(|l| {
-                let l = l.trim_end_matches('\r');
-                if l.is_empty() || l.starts_with(':') {
-                    return None;
-                }
-                if let Some(rest) = l.strip_prefix("data: ") {
-                    if rest == "[DONE]" {
-                        return None;
-                    }
-                    return Some(rest.to_string());
-                }
-                None
-            })
-            .collect()
+    async fn read_json<TResp: DeserializeOwned>(
+        call: &CallTelemetry,
+        resp: HttpResponse,
+    ) -> Result<RawResponse<TResp>, Error> {
+        let status = resp.status();
+        if !status.is_success() {
+            return Self::map_api_error(call, status, resp).await;
+        }
+        let meta = ResponseMeta::from_response(&resp);
+        let bytes = resp.bytes().await?;
+        Ok(RawResponse {
+            data: serde_json::from_slice(&bytes)?,
+            meta,
+        })
     }
 
     async fn map_api_error<TResp>(
+        call: &CallTelemetry,
         status: StatusCode,
-        resp: reqwest::Response,
+        resp: HttpResponse,
     ) -> Result<TResp, Error> {
+        let request_id = request_id(resp.headers());
+        let retry_after = crate::retry::server_delay(resp.headers());
         let text = resp.text().await.unwrap_or_default();
-        if let Ok(env) = serde_json::from_str::<ApiErrorEnvelope>(&text) {
+        let err = if let Ok(env) = serde_json::from_str::<ApiErrorEnvelope>(&text) {
             let mut api: ApiError = env.into();
             api.status = Some(status.as_u16());
-            Err(Error::Api(api))
+            api.request_id = request_id;
+            api.retry_after = retry_after;
+            api.into()
         } else {
-            Err(Error::UnexpectedStatus {
+            Error::UnexpectedStatus {
                 status: status.as_u16(),
                 body: text,
-            })
-        }
+                request_id,
+                retry_after,
+            }
+        };
+        call.error(&err);
+        Err(err)
     }
 }
 
 impl OpenAI {
-    async fn execute_with_retry<F>(&self, mk: F, _sse: bool) -> Result<reqwest::Response, Error>
-    where
-        F: Fn() -> reqwest::RequestBuilder,
-    {
+    fn telemetry(&self, method: &str, path: &str, model: Option<&str>) -> CallTelemetry {
+        let server = self.base_url.host_str();
+        CallTelemetry::start(self.metrics.clone(), method, path, model, server)
+    }
+
+    // Run a call through the circuit breaker of `path` and `model`, if one
+    // is configured, then through the retry loop, inside the call's span.
+    async fn execute(
+        &self,
+        call: &CallTelemetry,
+        path: &str,
+        model: Option<&str>,
+        req: HttpRequest,
+        idempotent: bool,
+        opts: &RequestOptions,
+    ) -> Result<HttpResponse, Error> {
+        let res = call
+            .instrument(async {
+                let Some(breaker) = &self.breaker else {
+                    return self.execute_with_retry(call, req, idempotent, opts).await;
+                };
+                let trial = breaker.enter(path, model)?;
+                let res = self.execute_with_retry(call, req, idempotent, opts).await;
+                trial.record(&res);
+                res
+            })
+            .await;
+        call.outcome(&res);
+        res
+    }
+
+    // Send `req`, retrying according to the retry policy.
+    // `idempotent` tells the policy whether a repeat can duplicate side effects.
+    // POSTs carry one idempotency key for the whole call, so the server can
+    // recognize retries of a request it already processed.
+    async fn execute_with_retry(
+        &self,
+        call: &CallTelemetry,
+        req: HttpRequest,
+        idempotent: bool,
+        opts: &RequestOptions,
+    ) -> Result<HttpResponse, Error> {
+        let extra_headers = opts.header_map()?;
+        let idempotency_key = match &opts.idempotency_key {
+            Some(key) => header::HeaderValue::from_str(key)
+                .map_err(|_| Error::InvalidHeader("Idempotency-Key".to_string()))?,
+            None => header::HeaderValue::from_str(&new_idempotency_key())
+                .expect("generated key is ASCII"),
+        };
+        let started = self.retry.get_max_elapsed().map(|_| Instant::now());
         let mut attempt = 0u32;
+        let mut prev_delay = None;
+        let mut reauthenticated = false;
         loop {
-            let req = mk();
-            let res = req.send().await;
-            return match res {
-                Ok(resp) => {
-                    let status = resp.status();
-                    if status.is_success() {
-                        return Ok(resp);
-                    }
-                    if self.should_retry_status(status) && attempt < self.max_retries {
-                        let delay =
-                            self.retry_delay(attempt, resp.headers().get(header::RETRY_AFTER));
-                        attempt += 1;
-                        sleep(delay).await;
-                        continue;
-                    }
-                    Ok(resp)
+            let cred = match &opts.api_key {
+                Some(key) => Credential::new(key.clone()),
+                None => self.credentials.get().await?,
+            };
+            let attempt_req =
+                self.finalize(req.clone(), &cred, opts, &idempotency_key, &extra_headers)?;
+            let next = Next::new(self.transport.as_ref(), &self.middleware);
+            let res = call.attempt(attempt, next.run(attempt_req)).await;
+            if let Ok(resp) = &res {
+                let status = resp.status();
+                if status.is_success() {
+                    return res;
                 }
-                Err(e) => {
-                    println!("Request error: {}", e);
-                    if self.is_retryable_error(&e) && attempt < self.max_retries {
-                        let delay = self.retry_delay(attempt, None);
-                        attempt += 1;
-                        sleep(delay).await;
-                        continue;
-                    }
-                    Err(Error::Http(e))
+                // The credential may have been revoked or rotated early;
+                // fetch a fresh one and try once more before giving up.
+                if status == StatusCode::UNAUTHORIZED
+                    && !reauthenticated
+                    && opts.api_key.is_none()
+                    && self.credentials.can_refresh()
+                {
+                    reauthenticated = true;
+                    self.credentials.invalidate(&cred).await;
+                    continue;
                 }
+            }
+            let (status, headers) = match &res {
+                Ok(resp) => (Some(resp.status()), Some(resp.headers())),
+                Err(_) => (None, None),
             };
+            let ctx = RetryContext {
+                attempt,
+                status,
+                headers,
+                error: res.as_ref().err(),
+                idempotent,
+            };
+            if !self.retry.should_retry(&ctx) {
+                return res;
+            }
+            let delay = self.retry.delay(attempt, prev_delay, headers);
+            if let (Some(start), Some(budget)) = (started, self.retry.get_max_elapsed()) {
+                if start.elapsed() + delay > budget {
+                    return res;
+                }
+            }
+            drop(res);
+            attempt += 1;
+            prev_delay = Some(delay);
+            sleep(delay).await;
         }
     }
+}
+
+/// Endpoint methods that return [`RawResponse`], created by
+/// [`OpenAI::with_raw_response`].
+pub struct WithRawResponse<'a> {
+    client: &'a OpenAI,
+    opts: RequestOptions,
+}
+
+impl WithRawResponse<'_> {
+    /// Use `opts` for the calls made through this value.
+    pub fn options(mut self, opts: RequestOptions) -> Self {
+        self.opts = opts;
+        self
+    }
 
-    fn should_retry_status(&self, status: StatusCode) -> bool {
-        status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    pub async fn chat_completion(
+        &self,
+        req: ChatCompletionRequest,
+    ) -> Result<RawResponse<ChatCompletionResponse>, Error> {
+        self.client
+            .post_json("/v1/chat/completions", &req, true, &self.opts)
+            .await
     }
 
-    fn retry_delay(
+    pub async fn embeddings(
         &self,
-        attempt: u32,
-        retry_after: Option<&header::HeaderValue>,
-    ) -> std::time::Duration {
-        if let Some(v) = retry_after {
-            if let Ok(s) = v.to_str() {
-                if let Ok(secs) = s.parse::<u64>() {
-                    return Duration::from_secs(secs);
-                }
-            }
+        req: EmbeddingsRequest,
+    ) -> Result<RawResponse<EmbeddingsResponse>, Error> {
+        self.client
+            .post_json("/v1/embeddings", &req, true, &self.opts)
+            .await
+    }
+
+    pub async fn chat_completion_stream(
+        &self,
+        mut req: ChatCompletionRequest,
+    ) -> Result<RawResponse<BoxStream<'static, Result<ChatCompletionChunk, Error>>>, Error> {
+        req.stream = Some(true);
+        self.client
+            .post_sse("/v1/chat/completions", &req, &self.opts)
+            .await
+    }
+
+    pub async fn responses(
+        &self,
+        req: ResponsesRequest,
+    ) -> Result<RawResponse<ResponsesResponse>, Error> {
+        self.client
+            .post_json("/v1/responses", &req, true, &self.opts)
+            .await
+    }
+
+    /// Stream a response. If the connection drops mid-stream, a stored or
+    /// background response is resumed after the last event received; the
+    /// stream continues without repeating events.
+    pub async fn responses_stream(
+        &self,
+        mut req: ResponsesRequest,
+    ) -> Result<RawResponse<BoxStream<'static, Result<ResponseStreamEvent, Error>>>, Error> {
+        req.stream = Some(true);
+        // `store` defaults to true on the server.
+        let resumable = req.background == Some(true) || req.store != Some(false);
+        let raw = self
+            .client
+            .post_sse("/v1/responses", &req, &self.opts)
+            .await?;
+        let client = self.client.clone();
+        let opts = self.opts.clone();
+        Ok(RawResponse {
+            data: resume_on_disconnect(client, raw.data, resumable, opts),
+            meta: raw.meta,
+        })
+    }
+
+    pub async fn responses_stream_resume(
+        &self,
+        response_id: &str,
+        starting_after: Option<u64>,
+    ) -> Result<RawResponse<BoxStream<'static, Result<ResponseStreamEvent, Error>>>, Error> {
+        let mut url = self
+            .client
+            .endpoint_url(&format!("/v1/responses/{}", response_id), None)?;
+        url.query_pairs_mut().append_pair("stream", "true");
+        if let Some(n) = starting_after {
+            url.query_pairs_mut()
+                .append_pair("starting_after", &n.to_string());
         }
-        let base = self.retry_base_delay_ms;
-        let backoff = base.saturating_mul(1u64 << attempt.min(8));
-        Duration::from_millis(backoff)
+        self.client
+            .get_sse("/v1/responses/{id}", url, &self.opts)
+            .await
     }
 
-    fn is_retryable_error(&self, e: &reqwest::Error) -> bool {
-        let mut result = e.is_timeout() || e.is_request();
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            result = result || e.is_connect()
+    pub async fn images_generate(
+        &self,
+        req: ImageGenerationRequest,
+    ) -> Result<RawResponse<ImageGenerationResponse>, Error> {
+        self.client
+            .post_json("/v1/images/generations", &req, false, &self.opts)
+            .await
+    }
+
+    pub async fn files_list(&self) -> Result<RawResponse<FileListResponse>, Error> {
+        self.client.get_json("/v1/files", &self.opts).await
+    }
+
+    pub async fn files_upload_bytes(
+        &self,
+        filename: &str,
+        bytes: Vec<u8>,
+        purpose: &str,
+    ) -> Result<RawResponse<FileObject>, Error> {
+        let (client, opts) = (self.client, &self.opts);
+        let mut form = Multipart::new().text("purpose", purpose);
+        for (name, value) in &opts.extra_body {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            form = form.text(name, &value);
         }
-        result = result;
-        result
+        let form = form.file("file", filename, &bytes);
+        let mut req = HttpRequest::new(Method::POST, client.endpoint_url("/v1/files", None)?);
+        let content_type = header::HeaderValue::from_str(&form.content_type())
+            .expect("boundary is a valid header value");
+        req.headers.insert(header::CONTENT_TYPE, content_type);
+        req.body = form.finish();
+
+        let call = client.telemetry("POST", "/v1/files", None);
+        let resp = client
+            .execute(&call, "/v1/files", None, req, false, opts)
+            .await?;
+        OpenAI::read_json(&call, resp).await
+    }
+
+    pub async fn files_download(&self, file_id: &str) -> Result<RawResponse<Vec<u8>>, Error> {
+        let client = self.client;
+        let url = client.endpoint_url(&format!("/v1/files/{}/content", file_id), None)?;
+        let req = HttpRequest::new(Method::GET, url);
+        let route = "/v1/files/{id}/content";
+        let call = client.telemetry("GET", route, None);
+        let resp = client
+            .execute(&call, route, None, req, true, &self.opts)
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return OpenAI::map_api_error(&call, status, resp).await;
+        }
+        let meta = ResponseMeta::from_response(&resp);
+        Ok(RawResponse {
+            data: resp.bytes().await?.to_vec(),
+            meta,
+        })
+    }
+
+    pub async fn files_delete(
+        &self,
+        file_id: &str,
+    ) -> Result<RawResponse<FileDeleteResponse>, Error> {
+        let client = self.client;
+        let url = client.endpoint_url(&format!("/v1/files/{}", file_id), None)?;
+        let req = HttpRequest::new(Method::DELETE, url);
+        let route = "/v1/files/{id}";
+        let call = client.telemetry("DELETE", route, None);
+        let resp = client
+            .execute(&call, route, None, req, true, &self.opts)
+            .await?;
+        OpenAI::read_json(&call, resp).await
+    }
+}
+
+// End `body` with `Error::StreamTimeout` when a chunk takes too long: the
+// first one gets the remaining first-byte budget (with the configured
+// limit for the error), later ones the idle timeout.
+fn watch_body(
+    mut body: ByteStream,
+    first_byte: Option<(Duration, Duration)>,
+    idle: Option<Duration>,
+) -> ByteStream {
+    if first_byte.is_none() && idle.is_none() {
+        return body;
     }
+    let idle = idle.map(|d| (d, d, StreamTimeoutKind::Idle));
+    let stream = try_stream! {
+        let mut wait = first_byte
+            .map(|(left, limit)| (left, limit, StreamTimeoutKind::FirstByte))
+            .or(idle);
+        loop {
+            let next = match wait {
+                Some((left, after, kind)) => match timeout(left, body.next()).await {
+                    Some(next) => next,
+                    None => Err(Error::StreamTimeout { kind, after })?,
+                },
+                None => body.next().await,
+            };
+            let Some(chunk) = next else { break };
+            yield chunk?;
+            wait = idle;
+        }
+    };
+    Box::pin(stream)
+}
+
+// An error sent inside an event stream: an `error` event, or a payload
+// with an `error` object like the body of a failed request.
+fn stream_error(
+    event: &str,
+    data: &serde_json::Value,
+    request_id: &Option<String>,
+) -> Option<Error> {
+    let body = match data.get("error") {
+        Some(error) if error.is_object() => error,
+        _ if event == "error" || data.get("type").and_then(|t| t.as_str()) == Some("error") => data,
+        _ => return None,
+    };
+    let mut api: ApiError = match ApiErrorBody::deserialize(body) {
+        Ok(body) => body.into(),
+        Err(_) => ApiErrorBody {
+            message: body.to_string(),
+            type_: None,
+            param: None,
+            code: None,
+        }
+        .into(),
+    };
+    api.request_id = request_id.clone();
+    Some(api.into())
+}
+
+fn accept_event_stream(req: &mut HttpRequest) {
+    req.headers.insert(
+        header::ACCEPT,
+        header::HeaderValue::from_static("text/event-stream"),
+    );
+}
+
+// Follow a Responses stream and, when the connection drops, reopen it after
+// the last `sequence_number` seen. Events at or before that number are
+// skipped, so none is yielded twice.
+fn resume_on_disconnect(
+    client: OpenAI,
+    mut events: BoxStream<'static, Result<ResponseStreamEvent, Error>>,
+    resumable: bool,
+    opts: RequestOptions,
+) -> BoxStream<'static, Result<ResponseStreamEvent, Error>> {
+    let max_reconnects = client.retry.get_max_retries() + 1;
+    let stream = try_stream! {
+        let mut response_id: Option<String> = None;
+        let mut last_seq: Option<u64> = None;
+        let mut reconnects = 0;
+        loop {
+            let err = match events.next().await {
+                None => break,
+                Some(Ok(ev)) => {
+                    if let (Some(seq), Some(last)) = (ev.sequence_number, last_seq) {
+                        if seq <= last {
+                            continue;
+                        }
+                    }
+                    last_seq = ev.sequence_number.or(last_seq);
+                    if response_id.is_none() {
+                        response_id = ev.response_id().map(str::to_string);
+                    }
+                    reconnects = 0;
+                    yield ev;
+                    continue;
+                }
+                Some(Err(e)) => e,
+            };
+            let id = match (resumable, &response_id) {
+                _ if transport_kind(&err).is_none() || reconnects >= max_reconnects => Err(err)?,
+                (true, Some(id)) => id.clone(),
+                (false, _) => Err(Error::StreamNotResumable {
+                    reason: "the response is not stored; set `store` or `background` to resume"
+                        .into(),
+                    source: Box::new(err),
+                })?,
+                (true, None) => Err(Error::StreamNotResumable {
+                    reason: "no response id was received before the connection dropped".into(),
+                    source: Box::new(err),
+                })?,
+            };
+            reconnects += 1;
+            let raw = client.with_raw_response().options(opts.clone());
+            events = raw.responses_stream_resume(&id, last_seq).await?.data;
+        }
+    };
+    Box::pin(stream)
+}
+
+// A POST request carrying `body` as JSON.
+fn json_request(url: Url, body: &serde_json::Value) -> Result<HttpRequest, Error> {
+    let mut req = HttpRequest::new(Method::POST, url);
+    req.headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    req.body = serde_json::to_vec(body)?.into();
+    Ok(req)
+}
+
+// A random key shared by all attempts of one call.
+fn new_idempotency_key() -> String {
+    format!("openai-sdk-rs-retry-{:032x}", fastrand::u128(..))
 }
 
 #[derive(Default)]
 pub struct OpenAIBuilder {
-    api_key: Option<String>,
+    api_key: Option<SecretString>,
+    credential_provider: Option<Arc<dyn CredentialProvider>>,
     base_url: Option<String>,
     org: Option<String>,
     project: Option<String>,
     timeout: Option<Duration>,
+    stream_first_byte_timeout: Option<Duration>,
+    stream_idle_timeout: Option<Duration>,
     user_agent: Option<String>,
-    max_retries: Option<u32>,
-    retry_base_delay_ms: Option<u64>,
-    http: Option<HttpClient>,
-    proxy: Option<String>,
+    retry: RetryPolicy,
+    #[cfg(feature = "reqwest")]
+    http: Option<reqwest::Client>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    connection: ConnectionOptions,
+    default_query: Vec<(String, String)>,
+    azure: Option<AzureConfig>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    rate_limiter: Option<RateLimiter>,
+    breaker: Option<CircuitBreaker>,
+    metrics: Option<Arc<dyn MetricsSink>>,
 }
 
 impl OpenAIBuilder {
     pub fn api_key(mut self, key: String) -> Self {
-        self.api_key = Some(key);
+        self.api_key = Some(key.into());
         self
     }
+    /// Authenticate with credentials from `provider` instead of a fixed key.
+    /// Takes precedence over [`OpenAIBuilder::api_key`].
+    pub fn credential_provider<P: CredentialProvider + 'static>(mut self, provider: P) -> Self {
+        self.credential_provider = Some(Arc::new(provider));
+        self
+    }
+    /// Endpoint paths are appended to the path of `url`, so gateways
+    /// mounted under a prefix work; a base ending in `/v1` is also accepted.
     pub fn base_url<S: Into<String>>(mut self, url: S) -> Self {
         self.base_url = Some(url.into());
         self
     }
+    /// Add a query parameter to every request. A per-call
+    /// [`RequestOptions::query`] of the same name replaces it.
+    pub fn default_query<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.default_query.push((name.into(), value.into()));
+        self
+    }
     pub fn org<S: Into<String>>(mut self, org: S) -> Self {
         self.org = Some(org.into());
         self
@@ -504,93 +1206,356 @@ impl OpenAIBuilder {
         self.timeout = Some(timeout);
         self
     }
+    /// Fail a stream with [`Error::StreamTimeout`] if no data arrives
+    /// within `timeout` of the call starting.
+    pub fn stream_first_byte_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_first_byte_timeout = Some(timeout);
+        self
+    }
+    /// Fail a stream with [`Error::StreamTimeout`] if it receives nothing
+    /// for `timeout`.
+    pub fn stream_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_idle_timeout = Some(timeout);
+        self
+    }
     pub fn user_agent<S: Into<String>>(mut self, ua: S) -> Self {
         self.user_agent = Some(ua.into());
         self
     }
     pub fn max_retries(mut self, n: u32) -> Self {
-        self.max_retries = Some(n);
+        self.retry = self.retry.max_retries(n);
         self
     }
     pub fn retry_base_delay(mut self, dur: Duration) -> Self {
-        self.retry_base_delay_ms = Some(dur.as_millis() as u64);
+        self.retry = self.retry.base_delay(dur);
         self
     }
-    pub fn http_client(mut self, client: HttpClient) -> Self {
+    /// Replace the retry policy, including any `max_retries` or
+    /// `retry_base_delay` set before.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+    #[cfg(feature = "reqwest")]
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
         self.http = Some(client);
         self
     }
+    /// Send requests through `transport` instead of the default reqwest
+    /// client. Takes precedence over [`OpenAIBuilder::http_client`].
+    pub fn transport<T: HttpTransport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+    /// Send all requests through this HTTP(S) proxy. `build` fails if the
+    /// URL is invalid.
     pub fn proxy<S: Into<String>>(mut self, url: S) -> Self {
-        self.proxy = Some(url.into());
+        self.connection.proxy = Some(url.into());
+        self
+    }
+    /// Close pooled connections that stay idle for `timeout`.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.connection.pool_idle_timeout = Some(timeout);
+        self
+    }
+    /// Keep at most `max` idle connections per host in the pool.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.connection.pool_max_idle_per_host = Some(max);
+        self
+    }
+    /// Send TCP keepalive probes on idle connections at this interval.
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.connection.tcp_keepalive = Some(interval);
+        self
+    }
+    /// Speak HTTP/2 without negotiating it first, e.g. for an h2c gateway.
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.connection.http2_prior_knowledge = true;
+        self
+    }
+    /// Trust the CA certificate(s) in `pem` in addition to the built-in
+    /// roots. May be called more than once.
+    pub fn add_root_certificate_pem<B: Into<Vec<u8>>>(mut self, pem: B) -> Self {
+        self.connection.root_certificates.push(pem.into());
+        self
+    }
+    /// Present a client certificate for mutual TLS. `pem` holds the
+    /// certificate chain and its private key.
+    pub fn identity_pem<B: Into<Vec<u8>>>(mut self, pem: B) -> Self {
+        self.connection.identity = Some(pem.into());
+        self
+    }
+    /// Append a middleware to the chain wrapped around every HTTP attempt.
+    /// Middleware run in the order they are added.
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+    /// Queue calls client-side so they stay within per-model RPM/TPM budgets.
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+    /// Fail calls fast while their endpoint keeps failing.
+    pub fn circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+    /// Report request, error, retry, latency and token metrics to `sink`.
+    pub fn metrics<M: MetricsSink + 'static>(mut self, sink: M) -> Self {
+        self.metrics = Some(Arc::new(sink));
+        self
+    }
+    /// Talk to Azure OpenAI. `base_url` must be the resource endpoint.
+    pub fn azure(mut self, config: AzureConfig) -> Self {
+        self.azure = Some(config);
         self
     }
 
+    /// Build a [`crate::blocking::OpenAI`] with these settings.
+    #[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+    pub fn build_blocking(self) -> Result<crate::blocking::OpenAI, Error> {
+        crate::blocking::OpenAI::from_async(self.build()?)
+    }
     pub fn build(self) -> Result<OpenAI, Error> {
-        let api_key = self.api_key.ok_or(Error::MissingApiKey)?;
+        let provider: Arc<dyn CredentialProvider> = match (self.credential_provider, self.api_key) {
+            (Some(provider), _) => provider,
+            (None, Some(key)) => Arc::new(StaticCredential::new(key)),
+            (None, None) => return Err(Error::MissingApiKey),
+        };
         let base_url_str = self
             .base_url
             .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
         let base_url = Url::parse(&base_url_str)?;
 
-        let http = if let Some(custom) = self.http {
-            custom
-        } else {
-            let mut headers = header::HeaderMap::new();
-            headers.insert(
-                header::CONTENT_TYPE,
-                header::HeaderValue::from_static("application/json"),
-            );
-
-            let mut http = HttpClient::builder().default_headers(headers);
-
-            if let Some(ua) = self.user_agent {
-                http = http.user_agent(ua);
-            } else {
-                http = http.user_agent(format!(
+        // An injected reqwest client keeps its own user agent.
+        #[cfg(feature = "reqwest")]
+        let custom_http = self.http.is_some();
+        #[cfg(not(feature = "reqwest"))]
+        let custom_http = false;
+        let user_agent = match self.user_agent {
+            Some(ua) => Some(
+                header::HeaderValue::from_str(&ua)
+                    .map_err(|_| Error::InvalidHeader("User-Agent".to_string()))?,
+            ),
+            None if custom_http => None,
+            None => Some(
+                header::HeaderValue::from_str(&format!(
                     "openai-sdk-rs/{} (+https://crates.io/crates/openai-sdk)",
                     env!("CARGO_PKG_VERSION")
-                ));
-            }
-
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                http = http.gzip(true).brotli(true);
-
-                if let Some(t) = self.timeout {
-                    http = http.timeout(t);
-                }
+                ))
+                .expect("version is a valid header value"),
+            ),
+        };
 
-            if let Some(px) = self.proxy {
-                if let Ok(proxy) = reqwest::Proxy::all(px) {
-                    http = http.proxy(proxy);
-                }
+        let transport = match self.transport {
+            Some(transport) => transport,
+            #[cfg(feature = "reqwest")]
+            None => {
+                let http = match self.http {
+                    Some(custom) => custom,
+                    None => default_http_client(self.connection)?,
+                };
+                Arc::new(ReqwestTransport::new(http))
             }
+            #[cfg(not(feature = "reqwest"))]
+            None => {
+                return Err(Error::Config(
+                    "no HTTP transport; enable the `reqwest` feature or call `transport`"
+                        .to_string(),
+                ))
             }
-
-            http.build()?
         };
 
         Ok(OpenAI {
-            http,
+            transport,
             base_url,
-            api_key,
+            // An injected client keeps its own timeout.
+            timeout: self.timeout.filter(|_| !custom_http),
+            stream_first_byte_timeout: self.stream_first_byte_timeout,
+            stream_idle_timeout: self.stream_idle_timeout,
+            user_agent,
+            default_query: self.default_query,
+            credentials: CredentialCache::new(provider),
             org: self.org,
             project: self.project,
-            max_retries: self.max_retries.unwrap_or(3),
-            retry_base_delay_ms: self.retry_base_delay_ms.unwrap_or(200),
+            azure: self.azure,
+            middleware: Arc::new(self.middleware),
+            retry: self.retry,
+            rate_limiter: self.rate_limiter,
+            breaker: self.breaker,
+            metrics: self.metrics,
         })
     }
 }
 
+// Settings for the default reqwest client. They are ignored when a client
+// or transport is injected.
+#[derive(Default)]
+struct ConnectionOptions {
+    proxy: Option<String>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    tcp_keepalive: Option<Duration>,
+    http2_prior_knowledge: bool,
+    root_certificates: Vec<Vec<u8>>,
+    identity: Option<Vec<u8>>,
+}
+
+#[cfg(feature = "reqwest")]
+fn default_http_client(conn: ConnectionOptions) -> Result<reqwest::Client, Error> {
+    let mut http = reqwest::Client::builder();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        http = http.gzip(true).brotli(true);
+
+        if let Some(px) = conn.proxy {
+            let proxy = reqwest::Proxy::all(&px)
+                .map_err(|e| Error::Config(format!("invalid proxy URL {:?}: {}", px, e)))?;
+            http = http.proxy(proxy);
+        }
+        if let Some(timeout) = conn.pool_idle_timeout {
+            http = http.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = conn.pool_max_idle_per_host {
+            http = http.pool_max_idle_per_host(max);
+        }
+        if let Some(interval) = conn.tcp_keepalive {
+            http = http.tcp_keepalive(interval);
+        }
+        if conn.http2_prior_knowledge {
+            http = http.http2_prior_knowledge();
+        }
+        for pem in &conn.root_certificates {
+            let certs = reqwest::Certificate::from_pem_bundle(pem)
+                .map_err(|e| Error::Config(format!("invalid root certificate: {}", e)))?;
+            if certs.is_empty() {
+                return Err(Error::Config(
+                    "invalid root certificate: no PEM certificate found".to_string(),
+                ));
+            }
+            for cert in certs {
+                http = http.add_root_certificate(cert);
+            }
+        }
+        if let Some(pem) = &conn.identity {
+            let identity = reqwest::Identity::from_pem(pem)
+                .map_err(|e| Error::Config(format!("invalid client identity: {}", e)))?;
+            http = http.identity(identity);
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    let _ = conn;
+
+    Ok(http.build()?)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::OpenAI;
+    #[test]
+    fn endpoint_urls_keep_the_base_path() {
+        use super::OpenAI;
+        use crate::AzureConfig;
+
+        let url = |base: &str| {
+            let client = OpenAI::builder()
+                .api_key("sk-test".into())
+                .base_url(base)
+                .transport(crate::ScriptedTransport::new())
+                .build()
+                .unwrap();
+            client
+                .endpoint_url("/v1/chat/completions", None)
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            url("https://api.openai.com"),
+            "https://api.openai.com/v1/chat/completions"
+        );
+        assert_eq!(
+            url("https://gw.internal/openai/"),
+            "https://gw.internal/openai/v1/chat/completions"
+        );
+        assert_eq!(
+            url("https://gw.internal/openai"),
+            "https://gw.internal/openai/v1/chat/completions"
+        );
+        assert_eq!(
+            url("http://localhost:4000/v1"),
+            "http://localhost:4000/v1/chat/completions"
+        );
+        assert_eq!(
+            url("http://localhost:4000/litellm/v1/"),
+            "http://localhost:4000/litellm/v1/chat/completions"
+        );
+
+        let azure = OpenAI::builder()
+            .api_key("key".into())
+            .base_url("https://gw.internal/aoai/")
+            .azure(AzureConfig::new("2024-10-21"))
+            .transport(crate::ScriptedTransport::new())
+            .build()
+            .unwrap();
+        assert_eq!(
+            azure
+                .endpoint_url("/v1/chat/completions", Some("gpt-4o"))
+                .unwrap()
+                .as_str(),
+            "https://gw.internal/aoai/openai/deployments/gpt-4o/chat/completions?api-version=2024-10-21"
+        );
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn invalid_connection_options_fail_the_build() {
+        use super::OpenAI;
+        use crate::Error;
+
+        let build = |b: super::OpenAIBuilder| b.api_key("sk-test".into()).build();
+        let err = build(OpenAI::builder().proxy("http://[::1")).unwrap_err();
+        assert!(
+            matches!(err, Error::Config(ref m) if m.contains("proxy")),
+            "{err:?}"
+        );
+        let err = build(OpenAI::builder().add_root_certificate_pem("not a pem")).unwrap_err();
+        assert!(
+            matches!(err, Error::Config(ref m) if m.contains("root")),
+            "{err:?}"
+        );
+        let err = build(OpenAI::builder().identity_pem("not a pem")).unwrap_err();
+        assert!(
+            matches!(err, Error::Config(ref m) if m.contains("identity")),
+            "{err:?}"
+        );
+        build(
+            OpenAI::builder()
+                .proxy("http://127.0.0.1:3128")
+                .pool_idle_timeout(std::time::Duration::from_secs(30))
+                .pool_max_idle_per_host(4)
+                .tcp_keepalive(std::time::Duration::from_secs(60))
+                .http2_prior_knowledge(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn azure_from_env_names_missing_endpoint() {
+        use super::{Error, OpenAI};
+
+        if std::env::var_os("AZURE_OPENAI_ENDPOINT").is_some() {
+            return;
+        }
+        let err = OpenAI::azure_from_env().unwrap_err();
+        assert!(matches!(&err, Error::Config(msg) if msg.contains("AZURE_OPENAI_ENDPOINT")));
+    }
 
     #[test]
     fn sse_extracts_data_lines() {
         let input =
             "event: message\n:data line as comment\ndata: {\"a\":1}\n\nretry: 5000\ndata: [DONE]\n";
-        let lines = OpenAI::sse_extract_data_lines(input);
+        let lines = crate::sse::extract_data_lines_from_str(input);
         assert_eq!(lines, vec!["{\"a\":1}".to_string()]);
     }
 }
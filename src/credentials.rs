@@ -0,0 +1,133 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::lock::Mutex;
+
+use crate::error::Error;
+use crate::secret::SecretString;
+use crate::utils::{BoxFuture, Instant};
+
+/// Credentials are refreshed this long before they expire, or halfway
+/// through their lifetime when that is shorter.
+const REFRESH_SKEW: Duration = Duration::from_secs(60);
+
+/// A secret sent with every request, plus an optional expiry.
+#[derive(Debug, Clone)]
+pub struct Credential {
+    pub token: SecretString,
+    pub expires_at: Option<Instant>,
+}
+
+impl Credential {
+    /// A credential that never expires, such as a plain API key.
+    pub fn new<S: Into<SecretString>>(token: S) -> Self {
+        Self {
+            token: token.into(),
+            expires_at: None,
+        }
+    }
+
+    /// A short-lived token, e.g. an OAuth or Entra ID access token.
+    pub fn expiring_in<S: Into<SecretString>>(token: S, ttl: Duration) -> Self {
+        Self {
+            token: token.into(),
+            expires_at: Some(Instant::now() + ttl),
+        }
+    }
+
+    // `fetched_at` is when the cache received the credential; it bounds the
+    // skew so that a token living a minute or less is still reused.
+    fn is_fresh(&self, fetched_at: Instant) -> bool {
+        self.expires_at.is_none_or(|at| {
+            let skew = REFRESH_SKEW.min(at.saturating_duration_since(fetched_at) / 2);
+            Instant::now() + skew < at
+        })
+    }
+}
+
+/// Source of the credential used to authenticate requests.
+///
+/// The client caches the returned [`Credential`] and asks for a new one
+/// shortly before it expires, and once more when the API answers `401`
+/// unless [`can_refresh`](Self::can_refresh) says a new fetch would return
+/// the same secret. Implementations only need to fetch; caching is handled by
+/// the client.
+pub trait CredentialProvider: Send + Sync {
+    fn credential(&self) -> BoxFuture<'_, Result<Credential, Error>>;
+
+    /// Whether fetching again may yield a different credential. Return
+    /// `false` for fixed secrets so a rejected request is not sent twice.
+    fn can_refresh(&self) -> bool {
+        true
+    }
+}
+
+/// A fixed API key.
+#[derive(Clone)]
+pub struct StaticCredential(SecretString);
+
+impl StaticCredential {
+    pub fn new<S: Into<SecretString>>(key: S) -> Self {
+        Self(key.into())
+    }
+}
+
+impl std::fmt::Debug for StaticCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("StaticCredential").finish_non_exhaustive()
+    }
+}
+
+impl CredentialProvider for StaticCredential {
+    fn credential(&self) -> BoxFuture<'_, Result<Credential, Error>> {
+        let cred = Credential::new(self.0.clone());
+        Box::pin(async move { Ok(cred) })
+    }
+
+    fn can_refresh(&self) -> bool {
+        false
+    }
+}
+
+// Shared cache in front of a provider. The async lock makes concurrent callers
+// wait for a single refresh instead of each hitting the provider.
+#[derive(Clone)]
+pub(crate) struct CredentialCache {
+    provider: Arc<dyn CredentialProvider>,
+    cached: Arc<Mutex<Option<(Credential, Instant)>>>,
+}
+
+impl CredentialCache {
+    pub(crate) fn new(provider: Arc<dyn CredentialProvider>) -> Self {
+        Self {
+            provider,
+            cached: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub(crate) async fn get(&self) -> Result<Credential, Error> {
+        let mut cached = self.cached.lock().await;
+        if let Some((cred, _)) = cached.as_ref().filter(|(c, at)| c.is_fresh(*at)) {
+            return Ok(cred.clone());
+        }
+        let cred = self.provider.credential().await?;
+        *cached = Some((cred.clone(), Instant::now()));
+        Ok(cred)
+    }
+
+    pub(crate) fn can_refresh(&self) -> bool {
+        self.provider.can_refresh()
+    }
+
+    // Drop the cached credential if it is still the one that was rejected, so
+    // a refresh done meanwhile by another request is kept.
+    pub(crate) async fn invalidate(&self, rejected: &Credential) {
+        let mut cached = self.cached.lock().await;
+        if cached
+            .as_ref()
+            .is_some_and(|(c, _)| c.token == rejected.token)
+        {
+            *cached = None;
+        }
+    }
+}
@@ -1,6 +1,10 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error as ThisError;
 
+use crate::transport::{transport_kind, TransportErrorKind};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiErrorBody {
     pub message: String,
@@ -16,24 +20,199 @@ pub struct ApiErrorEnvelope {
 }
 
 #[derive(Debug, ThisError)]
+#[non_exhaustive]
 pub enum Error {
     #[error("missing API key; set OPENAI_API_KEY or pass explicitly")]
     MissingApiKey,
 
+    #[cfg(feature = "reqwest")]
     #[error("http error: {0}")]
     Http(#[from] reqwest::Error),
 
+    #[error("transport error: {0}")]
+    Transport(#[from] crate::transport::TransportError),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("serialization error: {0}")]
     Serde(#[from] serde_json::Error),
 
     #[error("api error: {0}")]
-    Api(#[from] ApiError),
+    Api(#[from] Box<ApiError>),
 
     #[error("url parse error: {0}")]
     Url(#[from] url::ParseError),
 
+    #[error("invalid header value for {0}")]
+    InvalidHeader(String),
+
+    #[error("circuit open for {path}; retry in {retry_in:?}")]
+    CircuitOpen {
+        path: String,
+        model: Option<String>,
+        retry_in: std::time::Duration,
+    },
+
+    #[error("invalid configuration: {0}")]
+    Config(String),
+
+    #[error("stream timed out: {kind} after {after:?}")]
+    StreamTimeout {
+        kind: StreamTimeoutKind,
+        after: std::time::Duration,
+    },
+
+    #[error("stream interrupted and cannot be resumed: {reason}")]
+    StreamNotResumable {
+        reason: String,
+        #[source]
+        source: Box<Error>,
+    },
+
+    #[error("client pool has no backends")]
+    EmptyPool,
+
     #[error("unexpected status {status}: {body}")]
-    UnexpectedStatus { status: u16, body: String },
+    UnexpectedStatus {
+        status: u16,
+        body: String,
+        request_id: Option<String>,
+        /// Delay requested by `retry-after-ms` or `Retry-After`.
+        retry_after: Option<Duration>,
+    },
+}
+
+/// Broad category of an [`Error`], from [`Error::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Missing or invalid API key (401).
+    Authentication,
+    /// The key may not use this resource (403).
+    PermissionDenied,
+    /// Unknown model, file or endpoint (404).
+    NotFound,
+    /// Too many requests; retry after a delay (429).
+    RateLimit,
+    /// The account is out of credit. Reported as 429 but not worth retrying.
+    InsufficientQuota,
+    /// The prompt plus requested output exceed the model's context window.
+    ContextLengthExceeded,
+    /// The input or output was blocked by a content policy.
+    ContentFilter,
+    /// The server failed (5xx).
+    ServerError,
+    /// The request, or a stream, timed out.
+    Timeout,
+    /// Anything else: bad requests, configuration, decoding, I/O.
+    Other,
+}
+
+impl Error {
+    /// Classify this error by API error code or type, falling back to the
+    /// HTTP status.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Api(api) => classify(api.status, api.code.as_deref(), api.type_.as_deref()),
+            Error::UnexpectedStatus { status, .. } => classify(Some(*status), None, None),
+            Error::StreamTimeout { .. } => ErrorKind::Timeout,
+            Error::StreamNotResumable { source, .. } => source.kind(),
+            _ if transport_kind(self) == Some(TransportErrorKind::Timeout) => ErrorKind::Timeout,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// HTTP status of the failed response, if the server answered.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Api(api) => api.status,
+            Error::UnexpectedStatus { status, .. } => Some(*status),
+            Error::StreamNotResumable { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// Whether sending the same request again later may succeed.
+    ///
+    /// Follows the default [`crate::RetryPolicy`] as if the request were
+    /// idempotent: rate limits, server errors, timeouts, conflicts and
+    /// connection failures are retryable; exhausted quota is not.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind() {
+            ErrorKind::RateLimit | ErrorKind::ServerError | ErrorKind::Timeout => true,
+            ErrorKind::Other => match self {
+                Error::CircuitOpen { .. } => true,
+                Error::StreamNotResumable { source, .. } => source.is_retryable(),
+                _ => {
+                    self.status() == Some(409)
+                        || transport_kind(self) == Some(TransportErrorKind::Connect)
+                }
+            },
+            _ => false,
+        }
+    }
+
+    /// How long the server asked to wait before retrying, or how long an
+    /// open circuit stays open.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::Api(api) => api.retry_after,
+            Error::UnexpectedStatus { retry_after, .. } => *retry_after,
+            Error::CircuitOpen { retry_in, .. } => Some(*retry_in),
+            Error::StreamNotResumable { source, .. } => source.retry_after(),
+            _ => None,
+        }
+    }
+}
+
+fn classify(status: Option<u16>, code: Option<&str>, type_: Option<&str>) -> ErrorKind {
+    let is = |v: &str| code == Some(v) || type_ == Some(v);
+    if is("insufficient_quota") {
+        ErrorKind::InsufficientQuota
+    } else if is("context_length_exceeded") || is("string_above_max_length") {
+        ErrorKind::ContextLengthExceeded
+    } else if is("content_filter") || is("content_policy_violation") {
+        ErrorKind::ContentFilter
+    } else if is("rate_limit_exceeded") || is("rate_limit_error") {
+        ErrorKind::RateLimit
+    } else if is("invalid_api_key") || is("authentication_error") {
+        ErrorKind::Authentication
+    } else if is("permission_error") {
+        ErrorKind::PermissionDenied
+    } else if is("model_not_found") || is("not_found_error") {
+        ErrorKind::NotFound
+    } else if is("server_error") {
+        ErrorKind::ServerError
+    } else {
+        match status {
+            Some(401) => ErrorKind::Authentication,
+            Some(403) => ErrorKind::PermissionDenied,
+            Some(404) => ErrorKind::NotFound,
+            Some(408) => ErrorKind::Timeout,
+            Some(429) => ErrorKind::RateLimit,
+            Some(500..=599) => ErrorKind::ServerError,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// Which stream timeout of [`Error::StreamTimeout`] elapsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamTimeoutKind {
+    /// No data arrived within the first-byte timeout of the call.
+    FirstByte,
+    /// The stream went quiet for longer than the idle timeout.
+    Idle,
+}
+
+impl std::fmt::Display for StreamTimeoutKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StreamTimeoutKind::FirstByte => "no first byte",
+            StreamTimeoutKind::Idle => "idle",
+        })
+    }
 }
 
 #[derive(Debug, Clone, ThisError, Serialize, Deserialize)]
@@ -46,16 +225,117 @@ pub struct ApiError {
     pub code: Option<String>,
     #[serde(skip)]
     pub status: Option<u16>,
+    /// `x-request-id` of the failed response.
+    #[serde(skip)]
+    pub request_id: Option<String>,
+    /// Delay requested by `retry-after-ms` or `Retry-After`.
+    #[serde(skip)]
+    pub retry_after: Option<Duration>,
+}
+
+impl From<ApiError> for Error {
+    fn from(err: ApiError) -> Self {
+        Error::Api(Box::new(err))
+    }
 }
 
 impl From<ApiErrorEnvelope> for ApiError {
     fn from(env: ApiErrorEnvelope) -> Self {
+        env.error.into()
+    }
+}
+
+impl From<ApiErrorBody> for ApiError {
+    fn from(body: ApiErrorBody) -> Self {
         ApiError {
-            message: env.error.message,
-            type_: env.error.type_,
-            param: env.error.param,
-            code: env.error.code,
+            message: body.message,
+            type_: body.type_,
+            param: body.param,
+            code: body.code,
             status: None,
+            request_id: None,
+            retry_after: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api(status: u16, code: Option<&str>, type_: Option<&str>) -> Error {
+        ApiError {
+            message: "boom".into(),
+            type_: type_.map(Into::into),
+            param: None,
+            code: code.map(Into::into),
+            status: Some(status),
+            request_id: None,
+            retry_after: Some(Duration::from_secs(2)),
+        }
+        .into()
+    }
+
+    fn unexpected(status: u16) -> Error {
+        Error::UnexpectedStatus {
+            status,
+            body: "<html>".into(),
+            request_id: None,
+            retry_after: None,
+        }
+    }
+
+    #[test]
+    fn codes_take_precedence_over_status() {
+        let quota = api(429, Some("insufficient_quota"), Some("insufficient_quota"));
+        assert_eq!(quota.kind(), ErrorKind::InsufficientQuota);
+        assert!(!quota.is_retryable());
+
+        let limited = api(429, Some("rate_limit_exceeded"), Some("requests"));
+        assert_eq!(limited.kind(), ErrorKind::RateLimit);
+        assert!(limited.is_retryable());
+        assert_eq!(limited.retry_after(), Some(Duration::from_secs(2)));
+        assert_eq!(limited.status(), Some(429));
+
+        let context = api(400, Some("context_length_exceeded"), None);
+        assert_eq!(context.kind(), ErrorKind::ContextLengthExceeded);
+        assert!(!context.is_retryable());
+
+        let filtered = api(400, Some("content_filter"), None);
+        assert_eq!(filtered.kind(), ErrorKind::ContentFilter);
+    }
+
+    #[test]
+    fn statuses_classify_without_a_body() {
+        assert_eq!(unexpected(401).kind(), ErrorKind::Authentication);
+        assert_eq!(unexpected(403).kind(), ErrorKind::PermissionDenied);
+        assert_eq!(unexpected(404).kind(), ErrorKind::NotFound);
+        assert_eq!(unexpected(408).kind(), ErrorKind::Timeout);
+        assert_eq!(unexpected(502).kind(), ErrorKind::ServerError);
+        assert_eq!(unexpected(400).kind(), ErrorKind::Other);
+        assert!(unexpected(503).is_retryable());
+        assert!(unexpected(409).is_retryable());
+        assert!(!unexpected(400).is_retryable());
+        assert_eq!(unexpected(502).status(), Some(502));
+    }
+
+    #[test]
+    fn non_http_errors() {
+        let timeout = Error::StreamTimeout {
+            kind: StreamTimeoutKind::Idle,
+            after: Duration::from_secs(1),
+        };
+        assert_eq!(timeout.kind(), ErrorKind::Timeout);
+        assert!(timeout.is_retryable());
+        assert_eq!(timeout.status(), None);
+
+        let connect = Error::Transport(crate::TransportError::new(
+            TransportErrorKind::Connect,
+            "refused",
+        ));
+        assert_eq!(connect.kind(), ErrorKind::Other);
+        assert!(connect.is_retryable());
+
+        assert!(!Error::MissingApiKey.is_retryable());
+    }
+}
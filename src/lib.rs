@@ -82,11 +82,63 @@
 //! ```bash
 //! export OPENAI_API_KEY="your-api-key-here"
 //! ```
+//!
+//! ## Azure OpenAI
+//!
+//! Point the client at an Azure resource endpoint and map models to deployments:
+//!
+//! ```no_run
+//! use openai_sdk_rs::{AzureConfig, OpenAI};
+//!
+//! # fn main() -> Result<(), openai_sdk_rs::Error> {
+//! let client = OpenAI::builder()
+//!     .api_key("azure-api-key".to_string())
+//!     .base_url("https://my-resource.openai.azure.com")
+//!     .azure(AzureConfig::new("2024-10-21").deployment("gpt-4o-mini", "my-gpt4o-mini"))
+//!     .build()?;
+//! # let _ = client;
+//! # Ok(())
+//! # }
+//! ```
+mod azure;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+mod breaker;
 mod client;
+mod credentials;
 mod error;
+mod meta;
+pub mod metrics;
+mod middleware;
+mod multipart;
+mod options;
+mod pool;
+mod rate_limit;
+mod retry;
+mod secret;
 pub mod sse;
+mod telemetry;
+mod transport;
 pub mod types;
 mod utils;
 
-pub use crate::client::OpenAI;
-pub use crate::error::{ApiError, Error};
+pub use crate::azure::{AzureAuth, AzureConfig};
+pub use crate::breaker::{BreakerState, CircuitBreaker, StateChange};
+pub use crate::client::{OpenAI, WithRawResponse};
+pub use crate::credentials::{Credential, CredentialProvider, StaticCredential};
+pub use crate::error::{ApiError, ApiErrorBody, Error, ErrorKind, StreamTimeoutKind};
+pub use crate::meta::{RateLimitInfo, RawResponse, ResponseMeta};
+pub use crate::metrics::MetricsSink;
+pub use crate::middleware::{Middleware, Next};
+pub use crate::options::RequestOptions;
+pub use crate::pool::{OpenAIPool, OpenAIPoolBuilder, Routing};
+pub use crate::rate_limit::{RateLimit, RateLimiter};
+pub use crate::retry::{Jitter, RetryContext, RetryPolicy};
+pub use crate::secret::SecretString;
+#[cfg(feature = "reqwest")]
+pub use crate::transport::ReqwestTransport;
+pub use crate::transport::{
+    ByteStream, CassetteTransport, HttpRequest, HttpResponse, HttpTransport, ScriptedResponse,
+    ScriptedTransport, TransportError, TransportErrorKind,
+};
+pub use crate::utils::{BoxFuture, BoxStream};
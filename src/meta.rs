@@ -0,0 +1,149 @@
+use std::time::Duration;
+
+use http::header::HeaderMap;
+
+use crate::transport::HttpResponse;
+
+/// Rate-limit state reported by the `x-ratelimit-*` response headers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    pub limit_requests: Option<u64>,
+    pub limit_tokens: Option<u64>,
+    pub remaining_requests: Option<u64>,
+    pub remaining_tokens: Option<u64>,
+    /// Time until the request budget is fully restored.
+    pub reset_requests: Option<Duration>,
+    /// Time until the token budget is fully restored.
+    pub reset_tokens: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let num = |name: &str| header_str(headers, name).and_then(|v| v.parse().ok());
+        let dur = |name: &str| header_str(headers, name).and_then(parse_reset_duration);
+        Self {
+            limit_requests: num("x-ratelimit-limit-requests"),
+            limit_tokens: num("x-ratelimit-limit-tokens"),
+            remaining_requests: num("x-ratelimit-remaining-requests"),
+            remaining_tokens: num("x-ratelimit-remaining-tokens"),
+            reset_requests: dur("x-ratelimit-reset-requests"),
+            reset_tokens: dur("x-ratelimit-reset-tokens"),
+        }
+    }
+}
+
+/// HTTP metadata of a response.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseMeta {
+    pub status: u16,
+    /// `x-request-id`, needed when contacting OpenAI support.
+    pub request_id: Option<String>,
+    /// `openai-processing-ms`.
+    pub processing_time: Option<Duration>,
+    pub rate_limit: RateLimitInfo,
+    pub headers: HeaderMap,
+}
+
+impl ResponseMeta {
+    pub fn from_response(resp: &HttpResponse) -> Self {
+        let headers = resp.headers();
+        Self {
+            status: resp.status().as_u16(),
+            request_id: request_id(headers),
+            processing_time: header_str(headers, "openai-processing-ms")
+                .and_then(|v| v.parse::<f64>().ok())
+                .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok()),
+            rate_limit: RateLimitInfo::from_headers(headers),
+            headers: headers.clone(),
+        }
+    }
+}
+
+/// A typed response body together with its HTTP metadata.
+#[derive(Debug, Clone)]
+pub struct RawResponse<T> {
+    pub data: T,
+    pub meta: ResponseMeta,
+}
+
+impl<T> RawResponse<T> {
+    pub fn into_data(self) -> T {
+        self.data
+    }
+}
+
+pub(crate) fn request_id(headers: &HeaderMap) -> Option<String> {
+    header_str(headers, "x-request-id").map(str::to_string)
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
+
+// Parse Go-style durations used by the reset headers: "20ms", "1s", "6m0s",
+// "1h2m3.5s".
+pub(crate) fn parse_reset_duration(s: &str) -> Option<Duration> {
+    let mut total = 0f64;
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let num_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let value: f64 = rest[..num_len].parse().ok()?;
+        rest = &rest[num_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let secs = match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        total += value * secs;
+        rest = &rest[unit_len..];
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::parse_reset_duration;
+
+    #[test]
+    fn parses_reset_durations() {
+        assert_eq!(
+            parse_reset_duration("20ms"),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(parse_reset_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_reset_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(
+            parse_reset_duration("1h2m3.5s"),
+            Some(Duration::from_millis(3_723_500))
+        );
+        assert_eq!(parse_reset_duration("soon"), None);
+        assert_eq!(parse_reset_duration(""), None);
+        assert_eq!(parse_reset_duration(&format!("{}h", "9".repeat(30))), None);
+    }
+
+    #[test]
+    fn overflowing_headers_are_ignored() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("openai-processing-ms", "1e300".parse().unwrap());
+        headers.insert(
+            "x-ratelimit-reset-tokens",
+            "99999999999999999999999h".parse().unwrap(),
+        );
+        let resp = crate::HttpResponse::from_bytes(http::StatusCode::OK, headers, "");
+        let meta = super::ResponseMeta::from_response(&resp);
+        assert_eq!(meta.processing_time, None);
+        assert_eq!(meta.rate_limit.reset_tokens, None);
+    }
+}
@@ -0,0 +1,30 @@
+//! Client metrics, delivered to a [`MetricsSink`].
+//!
+//! Every metric carries an `endpoint` label (the API path, e.g.
+//! `/v1/chat/completions`) and, for calls with a model, a `model` label.
+
+/// Logical calls, after retries. Extra label: `status` (HTTP status, or
+/// `error` when no response was received).
+pub const REQUESTS: &str = "openai.client.requests";
+/// Failed calls. Extra label: `code`, the `ApiError.code` when the API sent
+/// one, else the HTTP status or the kind of transport error.
+pub const ERRORS: &str = "openai.client.errors";
+/// Retry attempts.
+pub const RETRIES: &str = "openai.client.retries";
+/// Seconds until the final response headers arrived, retries included.
+pub const DURATION: &str = "openai.client.duration";
+/// Seconds until the first event of a stream.
+pub const TIME_TO_FIRST_TOKEN: &str = "openai.client.time_to_first_token";
+/// Prompt tokens reported by `usage`, including the final event of streams.
+pub const INPUT_TOKENS: &str = "openai.client.tokens.input";
+/// Completion tokens reported by `usage`, including the final event of streams.
+pub const OUTPUT_TOKENS: &str = "openai.client.tokens.output";
+
+/// Receives counters and histograms from the client; forward them to the
+/// metrics library of your choice.
+///
+/// Methods are called on the request path, so they should not block.
+pub trait MetricsSink: Send + Sync {
+    fn counter(&self, name: &'static str, value: u64, labels: &[(&'static str, &str)]);
+    fn histogram(&self, name: &'static str, value: f64, labels: &[(&'static str, &str)]);
+}
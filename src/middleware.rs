@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::utils::BoxFuture;
+
+/// Hook around every HTTP attempt the client makes.
+///
+/// Middleware registered with [`crate::OpenAI::builder`] run in the order
+/// they were added, the first one being the outermost. Each receives the
+/// fully built request (URL, auth and body included) and decides whether and
+/// how to call the rest of the chain through [`Next::run`]. Retries go through
+/// the chain again, so every attempt is visible.
+///
+/// Streaming endpoints hand the response to middleware before the body is
+/// read; use [`HttpResponse::map_body`] to observe or rewrite SSE chunks.
+pub trait Middleware: Send + Sync {
+    fn handle<'a>(
+        &'a self,
+        req: HttpRequest,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Error>>;
+}
+
+/// The remainder of a middleware chain.
+pub struct Next<'a> {
+    transport: &'a dyn HttpTransport,
+    rest: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(transport: &'a dyn HttpTransport, chain: &'a [Arc<dyn Middleware>]) -> Self {
+        Self {
+            transport,
+            rest: chain,
+        }
+    }
+
+    /// Pass the request to the next middleware, or send it when none are left.
+    pub fn run(self, req: HttpRequest) -> BoxFuture<'a, Result<HttpResponse, Error>> {
+        match self.rest.split_first() {
+            Some((mw, rest)) => mw.handle(
+                req,
+                Next {
+                    transport: self.transport,
+                    rest,
+                },
+            ),
+            None => self.transport.send(req),
+        }
+    }
+}
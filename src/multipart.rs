@@ -0,0 +1,92 @@
+use bytes::Bytes;
+
+/// Minimal `multipart/form-data` encoder for file uploads.
+pub(crate) struct Multipart {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl Multipart {
+    pub(crate) fn new() -> Self {
+        Self {
+            boundary: format!("openai-sdk-rs-{:032x}", fastrand::u128(..)),
+            body: Vec::new(),
+        }
+    }
+
+    pub(crate) fn text(mut self, name: &str, value: &str) -> Self {
+        self.part_header(name, None, None);
+        self.body.extend_from_slice(value.as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    pub(crate) fn file(mut self, name: &str, filename: &str, bytes: &[u8]) -> Self {
+        self.part_header(name, Some(filename), Some("application/octet-stream"));
+        self.body.extend_from_slice(bytes);
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    pub(crate) fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    pub(crate) fn finish(mut self) -> Bytes {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        self.body.into()
+    }
+
+    fn part_header(&mut self, name: &str, filename: Option<&str>, content_type: Option<&str>) {
+        let mut head = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+            self.boundary,
+            escape(name)
+        );
+        if let Some(filename) = filename {
+            head.push_str(&format!("; filename=\"{}\"", escape(filename)));
+        }
+        head.push_str("\r\n");
+        if let Some(ct) = content_type {
+            head.push_str(&format!("Content-Type: {}\r\n", ct));
+        }
+        head.push_str("\r\n");
+        self.body.extend_from_slice(head.as_bytes());
+    }
+}
+
+// Percent-encode the characters that would end a quoted parameter, as
+// browsers do.
+fn escape(s: &str) -> String {
+    s.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Multipart;
+
+    #[test]
+    fn encodes_fields_and_files() {
+        let form = Multipart::new()
+            .text("purpose", "batch")
+            .file("file", "a\"b.jsonl", b"{}\n");
+        let boundary = form.boundary.clone();
+        assert_eq!(
+            form.content_type(),
+            format!("multipart/form-data; boundary={}", boundary)
+        );
+        let body = String::from_utf8(form.finish().to_vec()).unwrap();
+        assert_eq!(
+            body,
+            format!(
+                "--{b}\r\nContent-Disposition: form-data; name=\"purpose\"\r\n\r\nbatch\r\n\
+                 --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a%22b.jsonl\"\r\n\
+                 Content-Type: application/octet-stream\r\n\r\n{{}}\n\r\n--{b}--\r\n",
+                b = boundary
+            )
+        );
+    }
+}
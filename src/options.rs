@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::error::Error;
+use crate::impl_builder_methods;
+use crate::secret::SecretString;
+
+/// Per-call settings accepted by the `*_with_options` client methods.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Sent as `Idempotency-Key` on POST requests and reused by every retry of
+    /// the call. A random key is generated when unset.
+    pub idempotency_key: Option<String>,
+    /// Timeout for each attempt, replacing the client-wide timeout.
+    pub timeout: Option<Duration>,
+    /// Streams only: time allowed until the first body bytes arrive,
+    /// replacing the client-wide setting.
+    pub stream_first_byte_timeout: Option<Duration>,
+    /// Streams only: longest gap allowed between body chunks, replacing the
+    /// client-wide setting.
+    pub stream_idle_timeout: Option<Duration>,
+    /// Headers added to the request, replacing client defaults of the same name.
+    pub extra_headers: Vec<(String, String)>,
+    /// Query parameters appended to the URL.
+    pub extra_query: Vec<(String, String)>,
+    /// Fields merged into the top level of JSON bodies, replacing typed fields
+    /// of the same name. Uploads send them as extra form fields.
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
+    pub org: Option<String>,
+    pub project: Option<String>,
+    /// Authenticate this call with a fixed key instead of the client's credentials.
+    pub api_key: Option<SecretString>,
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn query<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.extra_query.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn body_field<K: Into<String>, V: Into<serde_json::Value>>(
+        mut self,
+        name: K,
+        value: V,
+    ) -> Self {
+        self.extra_body.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub(crate) fn header_map(&self) -> Result<HeaderMap, Error> {
+        let mut map = HeaderMap::new();
+        for (name, value) in &self.extra_headers {
+            let invalid = || Error::InvalidHeader(name.clone());
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+            let mut value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+            if is_credential_header(&name) {
+                value.set_sensitive(true);
+            }
+            map.insert(name, value);
+        }
+        Ok(map)
+    }
+}
+
+fn is_credential_header(name: &HeaderName) -> bool {
+    matches!(
+        name.as_str(),
+        "authorization" | "proxy-authorization" | "api-key"
+    )
+}
+
+impl_builder_methods!(
+    RequestOptions,
+    idempotency_key: String,
+    timeout: Duration,
+    stream_first_byte_timeout: Duration,
+    stream_idle_timeout: Duration,
+    org: String,
+    project: String
+);
@@ -0,0 +1,325 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::client::OpenAI;
+use crate::error::Error;
+use crate::transport::{transport_kind, TransportErrorKind};
+use crate::types::chat::{ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse};
+use crate::types::embeddings::{EmbeddingsRequest, EmbeddingsResponse};
+use crate::types::responses::{ResponseStreamEvent, ResponsesRequest, ResponsesResponse};
+use crate::utils::{BoxFuture, BoxStream, Instant};
+
+/// How [`OpenAIPool`] picks a backend for each call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Routing {
+    /// Spread calls in proportion to the backend weights.
+    #[default]
+    WeightedRoundRobin,
+    /// Prefer the backend with the lowest recent latency. Backends without
+    /// a measurement yet are tried first.
+    LeastLatency,
+}
+
+/// A client that spreads calls over several configured [`OpenAI`] backends,
+/// e.g. different keys, regions, Azure deployments or self-hosted servers.
+///
+/// A backend that fails with `429`, `5xx`, a timeout or a connection error
+/// (or whose circuit breaker is open)
+/// `unhealthy_after` times in a row is skipped for `cooldown`, and the failed
+/// call is repeated on the next backend. Other errors, such as `400`, are
+/// returned as is. Streams fail over until the response headers arrive;
+/// once events flow, errors are passed to the caller.
+///
+/// Each backend still applies its own retry policy before the pool moves on,
+/// so backends in a pool usually want few retries.
+#[derive(Clone)]
+pub struct OpenAIPool {
+    backends: Arc<Vec<OpenAI>>,
+    health: Arc<Mutex<Vec<Health>>>,
+    routing: Routing,
+    unhealthy_after: u32,
+    cooldown: Duration,
+}
+
+impl std::fmt::Debug for OpenAIPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenAIPool")
+            .field("backends", &self.backends)
+            .field("routing", &self.routing)
+            .field("unhealthy_after", &self.unhealthy_after)
+            .field("cooldown", &self.cooldown)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+struct Health {
+    weight: i64,
+    current: i64,
+    failures: u32,
+    down_until: Option<Instant>,
+    latency: Option<Duration>,
+}
+
+impl Health {
+    fn is_up(&self, now: Instant) -> bool {
+        match self.down_until {
+            Some(until) => until <= now,
+            None => true,
+        }
+    }
+}
+
+impl OpenAIPool {
+    pub fn builder() -> OpenAIPoolBuilder {
+        OpenAIPoolBuilder::default()
+    }
+
+    /// Run `f` against the selected backend, failing over to the others.
+    ///
+    /// Use this for endpoints without a pool method:
+    /// `pool.call(|c| Box::pin(c.images_generate(req.clone())))`.
+    pub async fn call<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: for<'c> Fn(&'c OpenAI) -> BoxFuture<'c, Result<T, Error>>,
+    {
+        let mut tried = Vec::with_capacity(self.backends.len());
+        loop {
+            let idx = self.pick(&tried);
+            tried.push(idx);
+            let started = Instant::now();
+            match f(&self.backends[idx]).await {
+                Ok(v) => {
+                    self.record_success(idx, started.elapsed());
+                    return Ok(v);
+                }
+                Err(e) if is_backend_failure(&e) => {
+                    self.record_failure(idx);
+                    if tried.len() == self.backends.len() {
+                        return Err(e);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub async fn chat_completion(
+        &self,
+        req: ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse, Error> {
+        self.call(|c| Box::pin(c.chat_completion(req.clone())))
+            .await
+    }
+
+    pub async fn chat_completion_stream(
+        &self,
+        req: ChatCompletionRequest,
+    ) -> Result<BoxStream<'static, Result<ChatCompletionChunk, Error>>, Error> {
+        self.call(|c| Box::pin(c.chat_completion_stream(req.clone())))
+            .await
+    }
+
+    pub async fn embeddings(&self, req: EmbeddingsRequest) -> Result<EmbeddingsResponse, Error> {
+        self.call(|c| Box::pin(c.embeddings(req.clone()))).await
+    }
+
+    pub async fn responses(&self, req: ResponsesRequest) -> Result<ResponsesResponse, Error> {
+        self.call(|c| Box::pin(c.responses(req.clone()))).await
+    }
+
+    pub async fn responses_stream(
+        &self,
+        req: ResponsesRequest,
+    ) -> Result<BoxStream<'static, Result<ResponseStreamEvent, Error>>, Error> {
+        self.call(|c| Box::pin(c.responses_stream(req.clone())))
+            .await
+    }
+
+    /// Whether backend `index` (in the order added) currently takes calls.
+    pub fn is_healthy(&self, index: usize) -> bool {
+        let health = self.health.lock().expect("pool lock");
+        health.get(index).is_some_and(|h| h.is_up(Instant::now()))
+    }
+
+    // Choose among the backends not yet tried for this call, preferring
+    // healthy ones. When all remaining backends are down, try them anyway.
+    fn pick(&self, tried: &[usize]) -> usize {
+        let mut health = self.health.lock().expect("pool lock");
+        let now = Instant::now();
+        let untried: Vec<usize> = (0..health.len()).filter(|i| !tried.contains(i)).collect();
+        let healthy: Vec<usize> = untried
+            .iter()
+            .copied()
+            .filter(|&i| health[i].is_up(now))
+            .collect();
+        let candidates = if healthy.is_empty() { untried } else { healthy };
+
+        match self.routing {
+            // Smooth weighted round-robin, as used by nginx.
+            Routing::WeightedRoundRobin => {
+                let total: i64 = candidates.iter().map(|&i| health[i].weight).sum();
+                let mut best = candidates[0];
+                for &i in &candidates {
+                    health[i].current += health[i].weight;
+                    if health[i].current > health[best].current {
+                        best = i;
+                    }
+                }
+                health[best].current -= total;
+                best
+            }
+            Routing::LeastLatency => candidates
+                .into_iter()
+                .min_by_key(|&i| health[i].latency.unwrap_or(Duration::ZERO))
+                .expect("pool has backends"),
+        }
+    }
+
+    fn record_success(&self, idx: usize, elapsed: Duration) {
+        let mut health = self.health.lock().expect("pool lock");
+        let h = &mut health[idx];
+        h.failures = 0;
+        h.down_until = None;
+        // Exponentially weighted average, so one slow call does not dominate.
+        h.latency = Some(match h.latency {
+            Some(prev) => prev.mul_f64(0.8) + elapsed.mul_f64(0.2),
+            None => elapsed,
+        });
+    }
+
+    fn record_failure(&self, idx: usize) {
+        let mut health = self.health.lock().expect("pool lock");
+        let h = &mut health[idx];
+        h.failures += 1;
+        if h.failures >= self.unhealthy_after {
+            h.down_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
+
+// Errors that say more about the backend than about the request.
+fn is_backend_failure(e: &Error) -> bool {
+    let status_failure = |s: u16| s == 429 || s >= 500;
+    match e {
+        Error::Api(api) => api.status.is_some_and(status_failure),
+        Error::UnexpectedStatus { status, .. } => status_failure(*status),
+        Error::CircuitOpen { .. } => true,
+        e => matches!(
+            transport_kind(e),
+            Some(TransportErrorKind::Connect | TransportErrorKind::Timeout)
+        ),
+    }
+}
+
+/// Builder for [`OpenAIPool`].
+#[derive(Debug)]
+pub struct OpenAIPoolBuilder {
+    backends: Vec<(OpenAI, u32)>,
+    routing: Routing,
+    unhealthy_after: u32,
+    cooldown: Duration,
+}
+
+impl Default for OpenAIPoolBuilder {
+    fn default() -> Self {
+        Self {
+            backends: Vec::new(),
+            routing: Routing::default(),
+            unhealthy_after: 3,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl OpenAIPoolBuilder {
+    /// Add a backend with weight 1.
+    pub fn backend(self, client: OpenAI) -> Self {
+        self.weighted_backend(client, 1)
+    }
+
+    /// Add a backend that receives `weight` shares of the traffic under
+    /// [`Routing::WeightedRoundRobin`].
+    pub fn weighted_backend(mut self, client: OpenAI, weight: u32) -> Self {
+        self.backends.push((client, weight.max(1)));
+        self
+    }
+
+    pub fn routing(mut self, routing: Routing) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Consecutive failures after which a backend is taken out of rotation.
+    pub fn unhealthy_after(mut self, failures: u32) -> Self {
+        self.unhealthy_after = failures.max(1);
+        self
+    }
+
+    /// How long an unhealthy backend is skipped before it is tried again.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    pub fn build(self) -> Result<OpenAIPool, Error> {
+        if self.backends.is_empty() {
+            return Err(Error::EmptyPool);
+        }
+        let health = self
+            .backends
+            .iter()
+            .map(|(_, weight)| Health {
+                weight: *weight as i64,
+                current: 0,
+                failures: 0,
+                down_until: None,
+                latency: None,
+            })
+            .collect();
+        Ok(OpenAIPool {
+            backends: Arc::new(self.backends.into_iter().map(|(c, _)| c).collect()),
+            health: Arc::new(Mutex::new(health)),
+            routing: self.routing,
+            unhealthy_after: self.unhealthy_after,
+            cooldown: self.cooldown,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OpenAI, OpenAIPool};
+
+    fn pool(weights: &[u32]) -> OpenAIPool {
+        let mut b = OpenAIPool::builder();
+        for &w in weights {
+            let client = OpenAI::builder()
+                .api_key("sk-test".into())
+                .transport(crate::ScriptedTransport::new())
+                .build()
+                .unwrap();
+            b = b.weighted_backend(client, w);
+        }
+        b.build().unwrap()
+    }
+
+    #[test]
+    fn weighted_round_robin_is_smooth() {
+        let p = pool(&[2, 1]);
+        let picks: Vec<usize> = (0..6).map(|_| p.pick(&[])).collect();
+        assert_eq!(picks, vec![0, 1, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn unhealthy_backends_are_skipped() {
+        let p = pool(&[1, 1]);
+        for _ in 0..3 {
+            p.record_failure(0);
+        }
+        assert!(!p.is_healthy(0));
+        assert!((0..4).all(|_| p.pick(&[]) == 1));
+        // With every other backend already tried, a down one is still used.
+        assert_eq!(p.pick(&[1]), 0);
+    }
+}
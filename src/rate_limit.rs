@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::meta::RateLimitInfo;
+use crate::utils::{sleep, Instant};
+
+/// Requests-per-minute and tokens-per-minute budget of one model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+}
+
+impl RateLimit {
+    pub fn new(requests_per_minute: u32, tokens_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute: Some(requests_per_minute),
+            tokens_per_minute: Some(tokens_per_minute),
+        }
+    }
+
+    pub fn requests_per_minute(rpm: u32) -> Self {
+        Self {
+            requests_per_minute: Some(rpm),
+            tokens_per_minute: None,
+        }
+    }
+
+    pub fn tokens_per_minute(tpm: u32) -> Self {
+        Self {
+            requests_per_minute: None,
+            tokens_per_minute: Some(tpm),
+        }
+    }
+}
+
+/// Client-side limiter that queues calls until the model's budget allows them.
+///
+/// Each model gets a requests bucket and a tokens bucket that refill
+/// continuously at the configured per-minute rate. A call reserves one request
+/// and an estimate of its tokens (prompt size plus the requested output
+/// limit); the reservation is corrected with the `usage` reported by the
+/// response, and the buckets are lowered to the `x-ratelimit-remaining-*`
+/// values when the server reports less than expected, e.g. because other
+/// clients share the key. The `x-ratelimit-reset-*` values then set when a
+/// lowered bucket is full again.
+///
+/// The limiter is cheap to clone; clones share state, so one instance can be
+/// given to several clients that use the same key.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    default: RateLimit,
+    models: HashMap<String, RateLimit>,
+    state: Arc<Mutex<HashMap<String, Buckets>>>,
+}
+
+impl std::fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("default", &self.default)
+            .field("models", &self.models)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RateLimiter {
+    /// A limiter applying `default` to every model without its own limit.
+    pub fn new(default: RateLimit) -> Self {
+        Self {
+            default,
+            ..Default::default()
+        }
+    }
+
+    /// Use `limit` for `model` instead of the default.
+    pub fn model<S: Into<String>>(mut self, model: S, limit: RateLimit) -> Self {
+        self.models.insert(model.into(), limit);
+        self
+    }
+
+    fn limit_for(&self, model: &str) -> RateLimit {
+        self.models.get(model).copied().unwrap_or(self.default)
+    }
+
+    // Wait until `model` has budget for one request of `tokens` tokens and
+    // reserve it.
+    pub(crate) async fn acquire(&self, model: &str, tokens: u32) -> Permit {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().expect("rate limiter lock");
+                let buckets = state
+                    .entry(model.to_string())
+                    .or_insert_with(|| Buckets::new(self.limit_for(model)));
+                buckets.try_take(Instant::now(), tokens)
+            };
+            match wait {
+                None => {
+                    return Permit {
+                        model: model.to_string(),
+                        tokens,
+                    }
+                }
+                Some(wait) => sleep(wait).await,
+            }
+        }
+    }
+
+    // Correct a reservation once the call is done, with the tokens it
+    // actually used and the budget the server reports, when known.
+    pub(crate) fn settle(&self, permit: &Permit, info: Option<&RateLimitInfo>, used: Option<u64>) {
+        let mut state = self.state.lock().expect("rate limiter lock");
+        if let Some(b) = state.get_mut(&permit.model) {
+            b.settle(Instant::now(), permit.tokens, info, used);
+        }
+    }
+}
+
+/// A reservation made by [`RateLimiter::acquire`].
+#[derive(Debug)]
+pub(crate) struct Permit {
+    model: String,
+    tokens: u32,
+}
+
+struct Buckets {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+}
+
+impl Buckets {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            requests: limit.requests_per_minute.map(Bucket::per_minute),
+            tokens: limit.tokens_per_minute.map(Bucket::per_minute),
+        }
+    }
+
+    // Take the budget if available, otherwise return how long to wait.
+    fn try_take(&mut self, now: Instant, tokens: u32) -> Option<Duration> {
+        // A call larger than the whole budget waits for a full bucket only.
+        let wanted_tokens = |b: &Bucket| (tokens as f64).min(b.capacity);
+        let mut wait = Duration::ZERO;
+        if let Some(b) = self.requests.as_mut() {
+            b.refill(now);
+            wait = wait.max(b.wait_for(1.0));
+        }
+        if let Some(b) = self.tokens.as_mut() {
+            b.refill(now);
+            wait = wait.max(b.wait_for(wanted_tokens(b)));
+        }
+        if !wait.is_zero() {
+            return Some(wait);
+        }
+        if let Some(b) = self.requests.as_mut() {
+            b.available -= 1.0;
+        }
+        if let Some(b) = self.tokens.as_mut() {
+            b.available -= tokens as f64;
+        }
+        None
+    }
+
+    // Return the unused part of the estimate, then cap the buckets at what
+    // the server reports as remaining. The server already counted this call,
+    // so its figure bounds the result rather than being corrected again.
+    fn settle(
+        &mut self,
+        now: Instant,
+        reserved: u32,
+        info: Option<&RateLimitInfo>,
+        used: Option<u64>,
+    ) {
+        if let (Some(b), Some(used)) = (self.tokens.as_mut(), used) {
+            b.give_back(reserved as f64 - used as f64);
+        }
+        let Some(info) = info else {
+            return;
+        };
+        if let Some(b) = self.requests.as_mut() {
+            b.align(now, info.remaining_requests, info.reset_requests);
+        }
+        if let Some(b) = self.tokens.as_mut() {
+            b.align(now, info.remaining_tokens, info.reset_tokens);
+        }
+    }
+}
+
+struct Bucket {
+    capacity: f64,
+    available: f64,
+    per_sec: f64,
+    updated: Option<Instant>,
+    // When the server said this bucket is full again; until then it refills
+    // linearly towards that point instead of at `per_sec`.
+    full_at: Option<Instant>,
+}
+
+impl Bucket {
+    fn per_minute(n: u32) -> Self {
+        Self {
+            capacity: n as f64,
+            available: n as f64,
+            per_sec: n as f64 / 60.0,
+            updated: None,
+            full_at: None,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if let Some(prev) = self.updated {
+            self.available = (self.available
+                + self.rate() * now.saturating_duration_since(prev).as_secs_f64())
+            .min(self.capacity);
+            if self.full_at.is_some_and(|full| full <= now) {
+                self.available = self.capacity;
+                self.full_at = None;
+            }
+        }
+        self.updated = Some(now);
+    }
+
+    // Tokens per second gained from `updated` on.
+    fn rate(&self) -> f64 {
+        match (self.full_at, self.updated) {
+            (Some(full), Some(at)) if full > at => {
+                (self.capacity - self.available) / (full - at).as_secs_f64()
+            }
+            _ => self.per_sec,
+        }
+    }
+
+    fn wait_for(&self, amount: f64) -> Duration {
+        let missing = amount - self.available;
+        let rate = self.rate();
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else if rate <= 0.0 {
+            Duration::from_secs(60)
+        } else {
+            Duration::from_secs_f64(missing / rate)
+        }
+    }
+
+    fn give_back(&mut self, amount: f64) {
+        self.available = (self.available + amount).min(self.capacity);
+    }
+
+    // Lower the bucket to the server's remaining budget and refill it by the
+    // time the server says that budget resets.
+    fn align(&mut self, now: Instant, remaining: Option<u64>, reset: Option<Duration>) {
+        let Some(remaining) = remaining else {
+            return;
+        };
+        self.refill(now);
+        if remaining as f64 >= self.available {
+            return;
+        }
+        self.available = remaining as f64;
+        if let Some(reset) = reset {
+            self.full_at = now.checked_add(reset);
+        }
+    }
+}
+
+/// Rough token count of a JSON request body: about four characters per
+/// token of text, plus the requested output limit.
+pub(crate) fn estimate_tokens(body: &serde_json::Value) -> u32 {
+    fn text_len(v: &serde_json::Value) -> usize {
+        match v {
+            serde_json::Value::String(s) => s.len(),
+            serde_json::Value::Array(items) => items.iter().map(text_len).sum(),
+            serde_json::Value::Object(map) => map.values().map(text_len).sum(),
+            _ => 0,
+        }
+    }
+    let prompt = ["messages", "input", "instructions", "prompt"]
+        .iter()
+        .filter_map(|k| body.get(k))
+        .map(text_len)
+        .sum::<usize>()
+        .div_ceil(4);
+    let output = ["max_completion_tokens", "max_output_tokens", "max_tokens"]
+        .iter()
+        .find_map(|k| body.get(k).and_then(|v| v.as_u64()))
+        .unwrap_or(0);
+    let n = body.get("n").and_then(|v| v.as_u64()).unwrap_or(1).max(1);
+    (prompt as u64 + output * n).min(u32::MAX as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{estimate_tokens, Buckets, RateLimit};
+    use crate::meta::RateLimitInfo;
+
+    #[test]
+    fn buckets_wait_and_refill() {
+        let start = Instant::now();
+        let mut b = Buckets::new(RateLimit::new(60, 600));
+        assert_eq!(b.try_take(start, 590), None);
+        // 10 tokens left; 100 more need (90 / 10 per second) = 9s.
+        assert_eq!(b.try_take(start, 100), Some(Duration::from_secs(9)));
+        assert_eq!(b.try_take(start + Duration::from_secs(9), 100), None);
+    }
+
+    #[test]
+    fn server_budget_caps_usage_refund_and_reset_refills() {
+        let start = Instant::now();
+        let mut b = Buckets::new(RateLimit::tokens_per_minute(600));
+        assert_eq!(b.try_take(start, 500), None);
+        // 498 of the 500 reserved tokens come back, but the server has only
+        // 50 left and resets in 5s: the bucket holds 50, not 548.
+        let info = RateLimitInfo {
+            remaining_tokens: Some(50),
+            reset_tokens: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        b.settle(start, 500, Some(&info), Some(2));
+        assert_eq!(b.try_take(start, 160), Some(Duration::from_secs(1)));
+        // Full at the reset, sooner than the 55s 600 TPM would take.
+        assert_eq!(b.try_take(start + Duration::from_secs(5), 600), None);
+    }
+
+    #[test]
+    fn estimates_prompt_and_output_tokens() {
+        let body = serde_json::json!({
+            "model": "gpt-4o-mini",
+            "messages": [{"role": "user", "content": "hello world!"}],
+            "max_completion_tokens": 100
+        });
+        // "user" + "hello world!" = 16 chars -> 4 tokens.
+        assert_eq!(estimate_tokens(&body), 104);
+    }
+}
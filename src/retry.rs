@@ -0,0 +1,314 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use http::header::{HeaderMap, RETRY_AFTER};
+use http::StatusCode;
+
+use crate::error::Error;
+use crate::transport::{transport_kind, TransportErrorKind};
+
+/// How the backoff delay is randomized between attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Jitter {
+    /// Plain exponential backoff.
+    #[default]
+    None,
+    /// A random delay between zero and the exponential backoff.
+    Full,
+    /// A random delay between the base delay and three times the previous
+    /// delay, capped at the maximum delay.
+    Decorrelated,
+}
+
+/// What a retry decision is based on.
+pub struct RetryContext<'a> {
+    /// Zero-based number of the attempt that just failed.
+    pub attempt: u32,
+    /// Status of the response, when one was received.
+    pub status: Option<StatusCode>,
+    /// Response headers, when a response was received.
+    pub headers: Option<&'a HeaderMap>,
+    /// Transport error, when no response was received.
+    pub error: Option<&'a Error>,
+    /// Whether repeating the request cannot duplicate its side effects.
+    pub idempotent: bool,
+}
+
+type Classifier = Arc<dyn Fn(&RetryContext<'_>) -> Option<bool> + Send + Sync>;
+
+/// Retry and backoff settings used for every request.
+///
+/// By default the client retries `408`, `409`, `429` and `5xx` responses and
+/// connection failures up to three times. Requests that are not idempotent,
+/// such as file uploads, are only retried when the server turned them away
+/// before processing (`408`, `429`, `503`) or the connection could not be
+/// made. A `502` may come from a gateway that already forwarded the request,
+/// so it is retried only for idempotent requests.
+/// The server can override the decision with an `x-should-retry` header and
+/// the delay with `retry-after-ms` or `Retry-After` (seconds or HTTP date).
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: Jitter,
+    max_elapsed: Option<Duration>,
+    classifier: Option<Classifier>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(60),
+            jitter: Jitter::None,
+            max_elapsed: None,
+            classifier: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_retries", &self.max_retries)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("max_elapsed", &self.max_elapsed)
+            .field("classifier", &self.classifier.is_some())
+            .finish()
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self::default().max_retries(0)
+    }
+
+    pub fn max_retries(mut self, n: u32) -> Self {
+        self.max_retries = n;
+        self
+    }
+
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Upper bound for a single backoff delay, including delays requested by
+    /// the server.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Stop retrying once the next attempt would start later than `total`
+    /// after the first one.
+    pub fn max_elapsed(mut self, total: Duration) -> Self {
+        self.max_elapsed = Some(total);
+        self
+    }
+
+    /// Decide retries yourself. Return `Some(true)` or `Some(false)` to force
+    /// the decision, or `None` to fall back to the default rules.
+    pub fn classifier<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&RetryContext<'_>) -> Option<bool> + Send + Sync + 'static,
+    {
+        self.classifier = Some(Arc::new(f));
+        self
+    }
+
+    pub fn get_max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    pub fn get_max_elapsed(&self) -> Option<Duration> {
+        self.max_elapsed
+    }
+
+    pub(crate) fn should_retry(&self, ctx: &RetryContext<'_>) -> bool {
+        if ctx.attempt >= self.max_retries {
+            return false;
+        }
+        if let Some(decision) = self.classifier.as_ref().and_then(|f| f(ctx)) {
+            return decision;
+        }
+        if let Some(v) = ctx.headers.and_then(|h| h.get("x-should-retry")) {
+            match v.to_str() {
+                Ok("true") => return true,
+                Ok("false") => return false,
+                _ => {}
+            }
+        }
+        if let Some(status) = ctx.status {
+            return match status.as_u16() {
+                408 | 429 | 503 => true,
+                409 | 500..=599 => ctx.idempotent,
+                _ => false,
+            };
+        }
+        match ctx.error.and_then(transport_kind) {
+            Some(TransportErrorKind::Connect) => true,
+            Some(TransportErrorKind::Timeout | TransportErrorKind::Request) => ctx.idempotent,
+            _ => false,
+        }
+    }
+
+    /// Delay before the attempt following `attempt`. `prev` is the previous
+    /// delay, used by decorrelated jitter.
+    pub(crate) fn delay(
+        &self,
+        attempt: u32,
+        prev: Option<Duration>,
+        headers: Option<&HeaderMap>,
+    ) -> Duration {
+        if let Some(hint) = headers.and_then(server_delay) {
+            return hint.min(self.max_delay);
+        }
+        let exp = self
+            .base_delay
+            .saturating_mul(1u32 << attempt.min(16))
+            .min(self.max_delay);
+        match self.jitter {
+            Jitter::None => exp,
+            Jitter::Full => random_between(Duration::ZERO, exp),
+            Jitter::Decorrelated => {
+                let upper = prev.unwrap_or(self.base_delay).saturating_mul(3);
+                random_between(self.base_delay, upper).min(self.max_delay)
+            }
+        }
+    }
+}
+
+fn random_between(lo: Duration, hi: Duration) -> Duration {
+    if hi <= lo {
+        return lo;
+    }
+    let lo_ms = lo.as_millis() as u64;
+    let hi_ms = hi.as_millis() as u64;
+    Duration::from_millis(fastrand::u64(lo_ms..=hi_ms))
+}
+
+// Delay requested by the server through `retry-after-ms` or `Retry-After`.
+pub(crate) fn server_delay(headers: &HeaderMap) -> Option<Duration> {
+    if let Some(ms) = headers
+        .get("retry-after-ms")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.trim().parse::<f64>().ok())
+    {
+        if let Ok(d) = Duration::try_from_secs_f64(ms / 1000.0) {
+            return Some(d);
+        }
+    }
+    let v = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = v.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+    let at = httpdate::parse_http_date(v).ok()?;
+    Some(
+        at.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use http::header::{HeaderMap, HeaderValue};
+    use http::StatusCode;
+
+    use super::{server_delay, Jitter, RetryContext, RetryPolicy};
+
+    fn ctx(status: u16, headers: Option<&HeaderMap>, idempotent: bool) -> RetryContext<'_> {
+        RetryContext {
+            attempt: 0,
+            status: Some(StatusCode::from_u16(status).unwrap()),
+            headers,
+            error: None,
+            idempotent,
+        }
+    }
+
+    #[test]
+    fn parses_retry_after_variants() {
+        let mut h = HeaderMap::new();
+        h.insert("retry-after", HeaderValue::from_static("2"));
+        assert_eq!(server_delay(&h), Some(Duration::from_secs(2)));
+
+        h.insert("retry-after-ms", HeaderValue::from_static("150.5"));
+        assert_eq!(server_delay(&h), Some(Duration::from_micros(150_500)));
+
+        let mut h = HeaderMap::new();
+        let at = SystemTime::now() + Duration::from_secs(30);
+        let date = httpdate::fmt_http_date(at);
+        h.insert("retry-after", HeaderValue::from_str(&date).unwrap());
+        let d = server_delay(&h).unwrap();
+        assert!(d > Duration::from_secs(28) && d <= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn server_hints_are_capped_and_never_panic() {
+        let p = RetryPolicy::default().max_delay(Duration::from_secs(5));
+        let mut h = HeaderMap::new();
+        h.insert("retry-after", HeaderValue::from_static("86400"));
+        assert_eq!(server_delay(&h), Some(Duration::from_secs(86400)));
+        assert_eq!(p.delay(0, None, Some(&h)), Duration::from_secs(5));
+
+        h.insert("retry-after", HeaderValue::from_static("1e30"));
+        assert_eq!(server_delay(&h), None);
+        h.insert("retry-after-ms", HeaderValue::from_static("1e300"));
+        assert_eq!(server_delay(&h), None);
+        assert_eq!(p.delay(0, None, Some(&h)), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn classifies_statuses() {
+        let p = RetryPolicy::default();
+        assert!(p.should_retry(&ctx(500, None, true)));
+        assert!(!p.should_retry(&ctx(500, None, false)));
+        assert!(p.should_retry(&ctx(503, None, false)));
+        assert!(p.should_retry(&ctx(502, None, true)));
+        assert!(!p.should_retry(&ctx(502, None, false)));
+        assert!(!p.should_retry(&ctx(400, None, true)));
+
+        let mut h = HeaderMap::new();
+        h.insert("x-should-retry", HeaderValue::from_static("false"));
+        assert!(!p.should_retry(&ctx(429, Some(&h), true)));
+
+        let p = p.classifier(|c| c.status.map(|s| s.as_u16() == 400));
+        assert!(p.should_retry(&ctx(400, None, true)));
+    }
+
+    #[test]
+    fn jittered_delays_stay_in_bounds() {
+        let p = RetryPolicy::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(1));
+        assert_eq!(p.delay(3, None, None), Duration::from_millis(800));
+        assert_eq!(p.delay(10, None, None), Duration::from_secs(1));
+
+        let full = p.clone().jitter(Jitter::Full);
+        for _ in 0..50 {
+            assert!(full.delay(2, None, None) <= Duration::from_millis(400));
+        }
+        let dec = p.jitter(Jitter::Decorrelated);
+        for _ in 0..50 {
+            let d = dec.delay(1, Some(Duration::from_millis(200)), None);
+            assert!(d >= Duration::from_millis(100) && d <= Duration::from_millis(600));
+        }
+    }
+}
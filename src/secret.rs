@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use zeroize::Zeroizing;
+
+/// An API key or token that stays out of logs.
+///
+/// `Debug` and `Display` print `[REDACTED]`. Clones share one allocation,
+/// which is zeroed when the last clone is dropped. Read the value with
+/// [`SecretString::expose_secret`].
+#[derive(Clone)]
+pub struct SecretString(Arc<Zeroizing<String>>);
+
+impl SecretString {
+    pub fn new<S: Into<String>>(secret: S) -> Self {
+        Self(Arc::new(Zeroizing::new(secret.into())))
+    }
+
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl PartialEq for SecretString {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.expose_secret() == other.expose_secret()
+    }
+}
+
+impl Eq for SecretString {}
+
+impl std::fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretString(\"[REDACTED]\")")
+    }
+}
+
+impl std::fmt::Display for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacted_and_shared() {
+        let key = SecretString::new("sk-live-123");
+        assert_eq!(format!("{key:?}"), "SecretString(\"[REDACTED]\")");
+        assert_eq!(key.to_string(), "[REDACTED]");
+        let copy = key.clone();
+        assert!(std::ptr::eq(copy.expose_secret(), key.expose_secret()));
+        assert_eq!(copy, SecretString::from("sk-live-123"));
+    }
+}
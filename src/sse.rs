@@ -1,29 +1,167 @@
 use serde::de::DeserializeOwned;
 
-// Extract "data: ..." lines from SSE bytes (excluding comments and [DONE])
-pub fn extract_data_lines_from_bytes(bytes: &[u8]) -> Vec<String> {
-    let mut out = Vec::new();
-    for line in bytes.split(|b| *b == b'\n') {
-        if line.is_empty() {
-            continue;
-        }
-        let mut l = line;
-        if let Some(last) = l.last() {
-            if *last == b'\r' {
-                l = &l[..l.len() - 1];
+/// One event of a `text/event-stream`, as dispatched by [`SseDecoder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The `event:` field, or `"message"` when the event has none.
+    pub event: String,
+    /// The `data:` fields of the event joined with `\n`.
+    pub data: String,
+    /// The last event id seen on the stream, as set by `id:` fields.
+    pub id: Option<String>,
+    /// Reconnection time in milliseconds, if a `retry:` field came with
+    /// this event or since the previous one.
+    pub retry: Option<u64>,
+}
+
+/// Incremental `text/event-stream` parser following the WHATWG
+/// specification.
+///
+/// Feed it body chunks as they arrive, split anywhere; it returns every
+/// event completed by a blank line. Lines may end in LF, CRLF or CR;
+/// multi-line `data:` fields are joined, comments and unknown fields are
+/// skipped, and events without data are not dispatched.
+///
+/// ```
+/// use openai_sdk_rs::sse::SseDecoder;
+///
+/// let mut decoder = SseDecoder::new();
+/// assert!(decoder.feed(b"event: delta\ndata: {\"a\":").is_empty());
+/// let events = decoder.feed(b"1}\ndata: {\"b\":2}\n\n");
+/// assert_eq!(events[0].event, "delta");
+/// assert_eq!(events[0].data, "{\"a\":1}\n{\"b\":2}");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SseDecoder {
+    line: Vec<u8>,
+    // The last chunk ended in CR; a leading LF of the next one belongs to it.
+    after_cr: bool,
+    started: bool,
+    event: String,
+    data: String,
+    has_data: bool,
+    id: Option<String>,
+    retry: Option<u64>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `chunk` and return the events it completes.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        const BOM: &[u8] = b"\xEF\xBB\xBF";
+        let mut out = Vec::new();
+        for &b in chunk {
+            if !self.started {
+                // The BOM may be split across chunks: hold its bytes in
+                // `line` until it is complete or ruled out.
+                if BOM.get(self.line.len()) == Some(&b) {
+                    self.line.push(b);
+                    if self.line.len() == BOM.len() {
+                        self.line.clear();
+                        self.started = true;
+                    }
+                    continue;
+                }
+                self.started = true;
+            }
+            if std::mem::take(&mut self.after_cr) && b == b'\n' {
+                continue;
+            }
+            match b {
+                b'\n' | b'\r' => {
+                    self.after_cr = b == b'\r';
+                    let line = std::mem::take(&mut self.line);
+                    if let Some(ev) = self.line_done(&line) {
+                        out.push(ev);
+                    }
+                }
+                _ => self.line.push(b),
             }
         }
-        if l.is_empty() || l[0] == b':' {
-            continue;
+        out
+    }
+
+    /// Dispatch an event left without its closing blank line. The spec
+    /// discards such an event when the stream ends; this is for callers
+    /// parsing text that may be cut short, such as a saved transcript.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let line = std::mem::take(&mut self.line);
+        let mut ev = None;
+        if !line.is_empty() {
+            ev = self.line_done(&line);
         }
-        if let Some(rest) = l.strip_prefix(b"data: ") {
-            if rest == b"[DONE]" {
-                continue;
+        ev.or_else(|| self.line_done(b""))
+    }
+
+    fn line_done(&mut self, line: &[u8]) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line[0] == b':' {
+            return None;
+        }
+        let line = String::from_utf8_lossy(line);
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (&*line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                if self.has_data {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value);
+                self.has_data = true;
             }
-            out.push(String::from_utf8(rest.to_vec()).unwrap_or_default());
+            "id" if !value.contains('\0') => self.id = Some(value.to_string()),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                // A value too large for u64 is ignored like any invalid one.
+                if let Ok(ms) = value.parse() {
+                    self.retry = Some(ms);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = std::mem::take(&mut self.event);
+        if !std::mem::take(&mut self.has_data) {
+            return None;
         }
+        Some(SseEvent {
+            event: if event.is_empty() {
+                "message".to_string()
+            } else {
+                event
+            },
+            data: std::mem::take(&mut self.data),
+            id: self.id.clone(),
+            retry: self.retry.take(),
+        })
     }
-    out
+}
+
+// Data of every event in a complete SSE body, without the `[DONE]` sentinel.
+fn event_data(bytes: &[u8]) -> Vec<String> {
+    let mut decoder = SseDecoder::new();
+    let mut events = decoder.feed(bytes);
+    events.extend(decoder.finish());
+    events
+        .into_iter()
+        .map(|ev| ev.data)
+        .filter(|data| data != "[DONE]")
+        .collect()
+}
+
+// Extract event data from SSE bytes (excluding comments and [DONE])
+pub fn extract_data_lines_from_bytes(bytes: &[u8]) -> Vec<String> {
+    event_data(bytes)
 }
 
 // Parse JSON values from SSE bytes. Invalid JSON lines are skipped.
@@ -43,6 +181,9 @@ pub fn extract_typed_from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Vec<T> {
 }
 
 // String variants
+//
+// Line-based: returns each `data: ` line as is, borrowing from `text`. Use
+// `extract_event_data_from_str` to join multi-line events.
 pub fn extract_data_lines_from_str(text: &str) -> Vec<&str> {
     text.lines()
         .filter_map(|l| {
@@ -56,17 +197,23 @@ pub fn extract_data_lines_from_str(text: &str) -> Vec<&str> {
         .collect()
 }
 
+// Extract the data of each event from SSE text (excluding [DONE]), decoded
+// like `extract_data_lines_from_bytes`.
+pub fn extract_event_data_from_str(text: &str) -> Vec<String> {
+    event_data(text.as_bytes())
+}
+
 pub fn extract_json_values_from_str(text: &str) -> Vec<serde_json::Value> {
-    extract_data_lines_from_str(text)
+    extract_event_data_from_str(text)
         .into_iter()
-        .filter_map(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+        .filter_map(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
         .collect()
 }
 
 pub fn extract_typed_from_str<T: DeserializeOwned>(text: &str) -> Vec<T> {
-    extract_data_lines_from_str(text)
+    extract_event_data_from_str(text)
         .into_iter()
-        .filter_map(|s| serde_json::from_str::<T>(s).ok())
+        .filter_map(|s| serde_json::from_str::<T>(&s).ok())
         .collect()
 }
 
@@ -75,8 +222,8 @@ pub fn try_extract_json_values_from_str(
     text: &str,
 ) -> Result<Vec<serde_json::Value>, serde_json::Error> {
     let mut out = Vec::new();
-    for s in extract_data_lines_from_str(text) {
-        out.push(serde_json::from_str::<serde_json::Value>(s)?);
+    for s in extract_event_data_from_str(text) {
+        out.push(serde_json::from_str::<serde_json::Value>(&s)?);
     }
     Ok(out)
 }
@@ -85,8 +232,54 @@ pub fn try_extract_typed_from_str<T: DeserializeOwned>(
     text: &str,
 ) -> Result<Vec<T>, serde_json::Error> {
     let mut out = Vec::new();
-    for s in extract_data_lines_from_str(text) {
-        out.push(serde_json::from_str::<T>(s)?);
+    for s in extract_event_data_from_str(text) {
+        out.push(serde_json::from_str::<T>(&s)?);
     }
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SseDecoder, SseEvent};
+
+    fn decode_in_pieces(input: &[u8], size: usize) -> Vec<SseEvent> {
+        let mut decoder = SseDecoder::new();
+        input.chunks(size).flat_map(|c| decoder.feed(c)).collect()
+    }
+
+    #[test]
+    fn decodes_fields_across_any_chunk_boundary() {
+        let input = b"\xEF\xBB\xBFevent: response.created\r\n: keep-alive\r\nid: 7\r\nretry: 3000\r\ndata: {\"a\":\r\ndata:1}\r\n\r\ndata: two\rdata\r\rignored: x\n\n";
+        for size in 1..input.len() {
+            let events = decode_in_pieces(input, size);
+            assert_eq!(
+                events,
+                vec![
+                    SseEvent {
+                        event: "response.created".into(),
+                        data: "{\"a\":\n1}".into(),
+                        id: Some("7".into()),
+                        retry: Some(3000),
+                    },
+                    SseEvent {
+                        event: "message".into(),
+                        data: "two\n".into(),
+                        id: Some("7".into()),
+                        retry: None,
+                    },
+                ],
+                "chunk size {size}"
+            );
+        }
+    }
+
+    #[test]
+    fn skips_events_without_data_and_holds_incomplete_ones() {
+        let mut decoder = SseDecoder::new();
+        assert!(decoder
+            .feed(b"event: ping\n\nretry: x\n\nretry: 5\nretry: 99999999999999999999\ndata: tail")
+            .is_empty());
+        let tail = decoder.finish().unwrap();
+        assert_eq!((tail.data.as_str(), tail.retry), ("tail", Some(5)));
+    }
+}
@@ -0,0 +1,336 @@
+//! Observability of calls: `tracing` spans (behind the `tracing` feature),
+//! [`MetricsSink`] metrics and the token usage reported by responses.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::metrics::{self, MetricsSink};
+use crate::transport::{transport_kind, HttpResponse, TransportErrorKind};
+use crate::utils::Instant;
+
+/// Token counts of a `usage` object. Chat Completions reports
+/// `prompt_tokens`/`completion_tokens`, the Responses API
+/// `input_tokens`/`output_tokens`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct TokenUsage {
+    pub input: u64,
+    pub output: u64,
+    pub total: u64,
+}
+
+impl TokenUsage {
+    pub(crate) fn from_usage(usage: &serde_json::Value) -> Option<Self> {
+        let field = |names: &[&str]| names.iter().find_map(|n| usage.get(n)?.as_u64());
+        let input = field(&["prompt_tokens", "input_tokens"]);
+        let output = field(&["completion_tokens", "output_tokens"]);
+        let total = field(&["total_tokens"]);
+        if input.is_none() && output.is_none() && total.is_none() {
+            return None;
+        }
+        let (input, output) = (input.unwrap_or(0), output.unwrap_or(0));
+        Some(Self {
+            input,
+            output,
+            total: total.unwrap_or(input + output),
+        })
+    }
+
+    /// Usage of a JSON response body or stream event: a top-level `usage`
+    /// (responses, chat chunks) or `response.usage` (Responses API events).
+    pub(crate) fn from_body(body: &[u8]) -> Option<Self> {
+        #[derive(serde::Deserialize)]
+        struct Inner {
+            usage: Option<serde_json::Value>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Probe {
+            usage: Option<serde_json::Value>,
+            response: Option<Inner>,
+        }
+        let probe = serde_json::from_slice::<Probe>(body).ok()?;
+        let usage = probe.usage.or(probe.response.and_then(|r| r.usage))?;
+        Self::from_usage(&usage)
+    }
+}
+
+/// One logical call, across all of its retry attempts.
+///
+/// With the `tracing` feature this is a span named after the OpenTelemetry
+/// GenAI conventions (`{gen_ai.operation.name} {gen_ai.request.model}`) with
+/// `gen_ai.*`, `http.*` and `error.type` attributes. With a metrics sink it
+/// reports the [`metrics`] of the call.
+pub(crate) struct CallTelemetry {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    endpoint: String,
+    model: Option<String>,
+    started: Instant,
+    first_chunk_seen: bool,
+}
+
+impl CallTelemetry {
+    pub(crate) fn start(
+        metrics: Option<Arc<dyn MetricsSink>>,
+        method: &str,
+        path: &str,
+        model: Option<&str>,
+        server: Option<&str>,
+    ) -> Self {
+        #[cfg(feature = "tracing")]
+        let span = {
+            let operation = operation_name(path);
+            let name = match model {
+                Some(model) => format!("{} {}", operation, model),
+                None => operation.to_string(),
+            };
+            tracing::info_span!(
+                "gen_ai.call",
+                otel.name = %name,
+                otel.kind = "client",
+                otel.status_code = tracing::field::Empty,
+                gen_ai.provider.name = "openai",
+                gen_ai.operation.name = operation,
+                gen_ai.request.model = model,
+                gen_ai.response.model = tracing::field::Empty,
+                gen_ai.response.time_to_first_chunk = tracing::field::Empty,
+                gen_ai.usage.input_tokens = tracing::field::Empty,
+                gen_ai.usage.output_tokens = tracing::field::Empty,
+                http.request.method = method,
+                http.response.status_code = tracing::field::Empty,
+                http.request.resend_count = tracing::field::Empty,
+                url.path = path,
+                server.address = server,
+                error.type = tracing::field::Empty,
+            )
+        };
+        #[cfg(not(feature = "tracing"))]
+        let _ = (method, server);
+        Self {
+            #[cfg(feature = "tracing")]
+            span,
+            metrics,
+            endpoint: path.to_string(),
+            model: model.map(str::to_string),
+            started: Instant::now(),
+            first_chunk_seen: false,
+        }
+    }
+
+    /// Run `fut` inside the call span.
+    pub(crate) fn instrument<F: Future>(&self, fut: F) -> impl Future<Output = F::Output> {
+        #[cfg(feature = "tracing")]
+        {
+            tracing::Instrument::instrument(fut, self.span.clone())
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            fut
+        }
+    }
+
+    /// Run one HTTP attempt inside its own span, a child of the call span.
+    pub(crate) async fn attempt<F>(&self, attempt: u32, fut: F) -> Result<HttpResponse, Error>
+    where
+        F: Future<Output = Result<HttpResponse, Error>>,
+    {
+        if attempt > 0 {
+            self.counter(metrics::RETRIES, 1, &[]);
+        }
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("http.request.resend_count", attempt);
+            let span = tracing::debug_span!(
+                parent: &self.span,
+                "http.attempt",
+                http.request.resend_count = attempt,
+                http.response.status_code = tracing::field::Empty,
+                error.type = tracing::field::Empty,
+            );
+            let res = tracing::Instrument::instrument(fut, span.clone()).await;
+            match &res {
+                Ok(resp) => {
+                    span.record("http.response.status_code", resp.status().as_u16());
+                }
+                Err(e) => {
+                    span.record("error.type", error_type(e));
+                    span.in_scope(|| tracing::warn!(error = %e, attempt, "request error"));
+                }
+            }
+            res
+        }
+        #[cfg(not(feature = "tracing"))]
+        fut.await
+    }
+
+    /// Record the final response or transport error of the retry loop.
+    /// Error responses are reported separately by [`CallTelemetry::api_error`]
+    /// once their body is decoded.
+    pub(crate) fn outcome(&self, res: &Result<HttpResponse, Error>) {
+        let status = match res {
+            Ok(resp) => resp.status().as_u16().to_string(),
+            Err(_) => "error".to_string(),
+        };
+        self.counter(metrics::REQUESTS, 1, &[("status", &status)]);
+        self.histogram(metrics::DURATION, self.started.elapsed().as_secs_f64(), &[]);
+        #[cfg(feature = "tracing")]
+        if let Ok(resp) = res {
+            self.span
+                .record("http.response.status_code", resp.status().as_u16());
+        }
+        if let Err(e) = res {
+            self.error(e);
+        }
+    }
+
+    /// Record a failure of the call.
+    pub(crate) fn error(&self, e: &Error) {
+        let code = error_type(e);
+        self.counter(metrics::ERRORS, 1, &[("code", &code)]);
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("otel.status_code", "ERROR");
+            self.span.record("error.type", code);
+        }
+    }
+
+    pub(crate) fn usage(&self, usage: TokenUsage) {
+        self.counter(metrics::INPUT_TOKENS, usage.input, &[]);
+        self.counter(metrics::OUTPUT_TOKENS, usage.output, &[]);
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("gen_ai.usage.input_tokens", usage.input);
+            self.span.record("gen_ai.usage.output_tokens", usage.output);
+        }
+    }
+
+    /// Record `gen_ai.response.model` from a JSON body or the first stream
+    /// event (`model`, or `response.model` for Responses API events).
+    pub(crate) fn response_model(&self, body: &[u8]) {
+        #[cfg(feature = "tracing")]
+        {
+            #[derive(serde::Deserialize)]
+            struct Inner {
+                model: Option<String>,
+            }
+            #[derive(serde::Deserialize)]
+            struct Probe {
+                model: Option<String>,
+                response: Option<Inner>,
+            }
+            let probe = serde_json::from_slice::<Probe>(body).ok();
+            let model = probe.and_then(|p| p.model.or(p.response.and_then(|r| r.model)));
+            if let Some(model) = model {
+                self.span.record("gen_ai.response.model", model.as_str());
+            }
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = body;
+    }
+
+    /// Observe one `data:` payload of a stream.
+    pub(crate) fn stream_data(&mut self, data: &[u8]) {
+        if !self.first_chunk_seen {
+            self.first_chunk_seen = true;
+            let ttft = self.started.elapsed().as_secs_f64();
+            self.histogram(metrics::TIME_TO_FIRST_TOKEN, ttft, &[]);
+            #[cfg(feature = "tracing")]
+            self.span
+                .record("gen_ai.response.time_to_first_chunk", ttft);
+            self.response_model(data);
+        }
+        // Only the final chunk of a stream carries usage; skip parsing the rest.
+        if contains(data, b"\"usage\"") {
+            if let Some(usage) = TokenUsage::from_body(data) {
+                self.usage(usage);
+            }
+        }
+    }
+
+    fn counter(&self, name: &'static str, value: u64, extra: &[(&'static str, &str)]) {
+        if let Some(sink) = &self.metrics {
+            sink.counter(name, value, &self.labels(extra));
+        }
+    }
+
+    fn histogram(&self, name: &'static str, value: f64, extra: &[(&'static str, &str)]) {
+        if let Some(sink) = &self.metrics {
+            sink.histogram(name, value, &self.labels(extra));
+        }
+    }
+
+    fn labels<'a>(&'a self, extra: &[(&'static str, &'a str)]) -> Vec<(&'static str, &'a str)> {
+        let mut labels = vec![("endpoint", self.endpoint.as_str())];
+        if let Some(model) = &self.model {
+            labels.push(("model", model.as_str()));
+        }
+        labels.extend_from_slice(extra);
+        labels
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+// `gen_ai.operation.name` of an endpoint path.
+#[cfg(feature = "tracing")]
+fn operation_name(path: &str) -> &'static str {
+    match path.trim_start_matches("/v1") {
+        "/chat/completions" | "/responses" => "chat",
+        "/completions" => "text_completion",
+        "/embeddings" => "embeddings",
+        "/images/generations" => "image_generation",
+        _ => "http",
+    }
+}
+
+// `error.type`: the API error code when there is one, else the status or the
+// kind of transport failure.
+fn error_type(e: &Error) -> String {
+    if let Some(kind) = transport_kind(e) {
+        return match kind {
+            TransportErrorKind::Connect => "connect",
+            TransportErrorKind::Timeout => "timeout",
+            TransportErrorKind::Request => "request",
+            TransportErrorKind::Body => "body",
+            TransportErrorKind::Other => "transport",
+        }
+        .to_string();
+    }
+    match e {
+        Error::Api(api) => api
+            .code
+            .clone()
+            .or_else(|| api.status.map(|s| s.to_string()))
+            .unwrap_or_else(|| "api_error".to_string()),
+        Error::UnexpectedStatus { status, .. } => status.to_string(),
+        Error::CircuitOpen { .. } => "circuit_open".to_string(),
+        Error::StreamTimeout { .. } => "stream_timeout".to_string(),
+        _ => "_OTHER".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TokenUsage;
+
+    #[test]
+    fn reads_usage_of_bodies_and_events() {
+        let chat =
+            br#"{"id":"1","usage":{"prompt_tokens":3,"completion_tokens":6,"total_tokens":9}}"#;
+        assert_eq!(
+            TokenUsage::from_body(chat),
+            Some(TokenUsage {
+                input: 3,
+                output: 6,
+                total: 9
+            })
+        );
+        let event = br#"{"type":"response.completed","response":{"usage":{"input_tokens":3,"output_tokens":4}}}"#;
+        assert_eq!(TokenUsage::from_body(event).map(|u| u.total), Some(7));
+        let chunk = br#"{"id":"1","choices":[],"usage":null}"#;
+        assert_eq!(TokenUsage::from_body(chunk), None);
+    }
+}
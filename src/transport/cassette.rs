@@ -0,0 +1,528 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_stream::try_stream;
+use bytes::Bytes;
+use futures_util::StreamExt;
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{HttpRequest, HttpResponse, HttpTransport, TransportError, TransportErrorKind};
+use crate::error::Error;
+use crate::utils::BoxFuture;
+
+const REDACTED: &str = "[REDACTED]";
+
+// Headers that carry credentials or account identifiers.
+const DEFAULT_REDACTED: &[&str] = &[
+    "authorization",
+    "api-key",
+    "cookie",
+    "set-cookie",
+    "openai-organization",
+    "openai-project",
+    "proxy-authorization",
+];
+
+// Query parameters that carry credentials, e.g. an `api-key` default query.
+const REDACTED_QUERY: &[&str] = &[
+    "api-key",
+    "api_key",
+    "apikey",
+    "key",
+    "access_token",
+    "token",
+    "sig",
+];
+
+/// Records request/response pairs into a JSON cassette file, or replays a
+/// cassette without touching the network.
+///
+/// Recording forwards every request to an inner transport and stores the
+/// response body in the chunks it arrived in, so a replayed SSE stream
+/// has the original chunk boundaries. Credential headers, header values
+/// marked sensitive and credential query parameters are redacted before
+/// anything is written. A body that fails partway is recorded with its
+/// error, which replay returns after the recorded chunks. The cassette is
+/// written by [`CassetteTransport::save`] and when the last clone is dropped.
+///
+/// Replay serves each recorded interaction once, picking the first unused
+/// one whose method, path and normalized body match the request. Bodies
+/// are compared as JSON values, so key order and whitespace do not matter,
+/// and multipart boundaries are ignored. Host and query are not compared.
+///
+/// ```no_run
+/// # #[cfg(feature = "reqwest")]
+/// # fn main() -> Result<(), openai_sdk_rs::Error> {
+/// use openai_sdk_rs::{CassetteTransport, OpenAI, ReqwestTransport};
+///
+/// let transport = if std::env::var("RECORD").is_ok() {
+///     CassetteTransport::record(ReqwestTransport::default(), "tests/cassettes/chat.json")
+/// } else {
+///     CassetteTransport::replay("tests/cassettes/chat.json")?
+/// };
+/// let client = OpenAI::builder()
+///     .api_key(std::env::var("OPENAI_API_KEY").unwrap_or_default())
+///     .transport(transport)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "reqwest"))]
+/// # fn main() {}
+/// ```
+#[derive(Clone)]
+pub struct CassetteTransport {
+    inner: Arc<Mutex<State>>,
+    upstream: Option<Arc<dyn HttpTransport>>,
+}
+
+impl std::fmt::Debug for CassetteTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.lock();
+        f.debug_struct("CassetteTransport")
+            .field("path", &state.path)
+            .field("recording", &self.upstream.is_some())
+            .field("interactions", &state.cassette.interactions.len())
+            .finish_non_exhaustive()
+    }
+}
+
+struct State {
+    path: PathBuf,
+    cassette: Cassette,
+    used: Vec<bool>,
+    redact: Vec<HeaderName>,
+    dirty: bool,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        if self.dirty {
+            let _ = self.write();
+        }
+    }
+}
+
+impl State {
+    fn write(&mut self) -> Result<(), Error> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(&self.cassette)?;
+        std::fs::write(&self.path, json + "\n")?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        let mut out = BTreeMap::<String, String>::new();
+        for (name, value) in headers {
+            let value = if value.is_sensitive() || self.redact.contains(name) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            out.entry(name.as_str().to_string())
+                .and_modify(|v| {
+                    v.push_str(", ");
+                    v.push_str(&value);
+                })
+                .or_insert(value);
+        }
+        out
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cassette {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    chunks: Vec<Chunk>,
+    // Set when reading the body failed after `chunks`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// A body chunk: text when it is valid UTF-8, raw bytes otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Chunk {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl Chunk {
+    fn from_bytes(bytes: &Bytes) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(s) => Chunk::Text(s.to_string()),
+            Err(_) => Chunk::Binary(bytes.to_vec()),
+        }
+    }
+
+    fn into_bytes(self) -> Bytes {
+        match self {
+            Chunk::Text(s) => s.into(),
+            Chunk::Binary(b) => b.into(),
+        }
+    }
+}
+
+impl CassetteTransport {
+    /// Forward requests to `upstream` and record them into the cassette at
+    /// `path`, replacing any existing file.
+    pub fn record<T, P>(upstream: T, path: P) -> Self
+    where
+        T: HttpTransport + 'static,
+        P: Into<PathBuf>,
+    {
+        Self {
+            inner: Arc::new(Mutex::new(State {
+                path: path.into(),
+                cassette: Cassette::default(),
+                used: Vec::new(),
+                redact: default_redacted(),
+                dirty: true,
+            })),
+            upstream: Some(Arc::new(upstream)),
+        }
+    }
+
+    /// Serve requests from the cassette at `path`.
+    pub fn replay<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let cassette: Cassette = serde_json::from_slice(&std::fs::read(path)?)?;
+        Ok(Self {
+            inner: Arc::new(Mutex::new(State {
+                path: path.to_path_buf(),
+                used: vec![false; cassette.interactions.len()],
+                cassette,
+                redact: default_redacted(),
+                dirty: false,
+            })),
+            upstream: None,
+        })
+    }
+
+    /// Also redact header `name` in recorded requests and responses.
+    ///
+    /// # Panics
+    ///
+    /// If `name` is not a valid header name.
+    pub fn redact_header(self, name: &str) -> Self {
+        let name = HeaderName::from_bytes(name.as_bytes()).expect("valid header name");
+        self.lock().redact.push(name);
+        self
+    }
+
+    /// Write the recorded interactions now.
+    pub fn save(&self) -> Result<(), Error> {
+        self.lock().write()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner.lock().expect("cassette lock")
+    }
+
+    fn replay_one(&self, req: &HttpRequest) -> Result<HttpResponse, Error> {
+        let body = normalized_body(req);
+        let mut state = self.lock();
+        let state = &mut *state;
+        let found = state
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .position(|(i, it)| {
+                !state.used[i]
+                    && it.request.method == req.method.as_str()
+                    && it.request.path == req.url.path()
+                    && it.request.body == body
+            });
+        let Some(i) = found else {
+            let msg = format!(
+                "no recorded interaction for {} {} in {}",
+                req.method,
+                req.url.path(),
+                state.path.display()
+            );
+            return Err(TransportError::new(TransportErrorKind::Other, msg).into());
+        };
+        state.used[i] = true;
+
+        let recorded = &state.cassette.interactions[i].response;
+        let status = StatusCode::from_u16(recorded.status)
+            .map_err(|e| TransportError::with_source(TransportErrorKind::Other, e))?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in &recorded.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        let chunks: Vec<_> = recorded
+            .chunks
+            .iter()
+            .cloned()
+            .map(Chunk::into_bytes)
+            .collect();
+        let error = recorded.error.clone().map(|msg| {
+            let err: Error = TransportError::new(TransportErrorKind::Body, msg).into();
+            Err(err)
+        });
+        let body = futures_util::stream::iter(chunks.into_iter().map(Ok).chain(error));
+        Ok(HttpResponse::new(status, headers, Box::pin(body)))
+    }
+}
+
+impl HttpTransport for CassetteTransport {
+    fn send(&self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            let Some(upstream) = &self.upstream else {
+                return self.replay_one(&req);
+            };
+            let request = {
+                let state = self.lock();
+                RecordedRequest {
+                    method: req.method.to_string(),
+                    path: req.url.path().to_string(),
+                    query: redacted_query(&req.url),
+                    headers: state.headers(&req.headers),
+                    body: normalized_body(&req),
+                }
+            };
+            let resp = upstream.send(req).await?;
+            let status = resp.status();
+            let headers = resp.headers().clone();
+            let mut recorder = Recorder {
+                state: self.inner.clone(),
+                interaction: Some(Interaction {
+                    request,
+                    response: RecordedResponse {
+                        status: status.as_u16(),
+                        headers: self.lock().headers(&headers),
+                        chunks: Vec::new(),
+                        error: None,
+                    },
+                }),
+            };
+            let mut body = resp.into_body();
+            let body = try_stream! {
+                while let Some(chunk) = body.next().await {
+                    let chunk = chunk.inspect_err(|e| recorder.fail(e))?;
+                    recorder.push(&chunk);
+                    yield chunk;
+                }
+                recorder.finish();
+            };
+            Ok(HttpResponse::new(status, headers, Box::pin(body)))
+        })
+    }
+}
+
+// Collects the chunks of one response. The interaction is stored when the
+// body ends or fails or, for bodies dropped early, with the chunks read so
+// far. Storing only marks the cassette dirty; files are written by `save`
+// and on drop, never while a stream is being polled.
+struct Recorder {
+    state: Arc<Mutex<State>>,
+    interaction: Option<Interaction>,
+}
+
+impl Recorder {
+    fn push(&mut self, chunk: &Bytes) {
+        if let Some(it) = &mut self.interaction {
+            it.response.chunks.push(Chunk::from_bytes(chunk));
+        }
+    }
+
+    fn fail(&mut self, err: &Error) {
+        if let Some(it) = &mut self.interaction {
+            it.response.error = Some(err.to_string());
+        }
+        self.finish();
+    }
+
+    fn finish(&mut self) {
+        let Some(it) = self.interaction.take() else {
+            return;
+        };
+        let mut state = self.state.lock().expect("cassette lock");
+        state.cassette.interactions.push(it);
+        state.used.push(true);
+        state.dirty = true;
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+fn default_redacted() -> Vec<HeaderName> {
+    DEFAULT_REDACTED
+        .iter()
+        .map(|h| HeaderName::from_static(h))
+        .collect()
+}
+
+// The query string with credential parameters redacted.
+fn redacted_query(url: &url::Url) -> Option<String> {
+    url.query()?;
+    let mut out = url::form_urlencoded::Serializer::new(String::new());
+    for (name, value) in url.query_pairs() {
+        let secret = REDACTED_QUERY.iter().any(|q| name.eq_ignore_ascii_case(q));
+        out.append_pair(&name, if secret { REDACTED } else { &value });
+    }
+    Some(out.finish())
+}
+
+// The body as compared during replay: parsed JSON when possible, text
+// otherwise, with any multipart boundary replaced by a fixed one.
+fn normalized_body(req: &HttpRequest) -> Option<Value> {
+    if req.body.is_empty() {
+        return None;
+    }
+    if let Ok(json) = serde_json::from_slice::<Value>(&req.body) {
+        return Some(json);
+    }
+    let mut text = String::from_utf8_lossy(&req.body).into_owned();
+    let boundary = req
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|ct| ct.split_once("boundary="))
+        .map(|(_, b)| b.trim_matches('"').to_string());
+    if let Some(boundary) = boundary {
+        text = text.replace(&boundary, "BOUNDARY");
+    }
+    Some(Value::String(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{ScriptedResponse, ScriptedTransport};
+    use http::Method;
+
+    fn request(body: &str) -> HttpRequest {
+        let mut req = HttpRequest::new(
+            Method::POST,
+            "https://api.openai.com/v1/chat/completions"
+                .parse()
+                .unwrap(),
+        );
+        req.headers.insert(
+            "authorization",
+            HeaderValue::from_static("Bearer sk-secret"),
+        );
+        req.body = Bytes::copy_from_slice(body.as_bytes());
+        req
+    }
+
+    #[tokio::test]
+    async fn records_redacted_chunks_and_replays_them() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", fastrand::u64(..)));
+        let scripted = ScriptedTransport::new();
+        scripted.push(ScriptedResponse::sse(["{\"a\":1}", "{\"a\":2}"]));
+
+        let recorder = CassetteTransport::record(scripted, &path);
+        let resp = recorder
+            .send(request(r#"{"model":"m","n":1}"#))
+            .await
+            .unwrap();
+        assert_eq!(resp.bytes().await.unwrap().len(), 44);
+        drop(recorder);
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("sk-secret"));
+        assert!(saved.contains(REDACTED));
+
+        let replay = CassetteTransport::replay(&path).unwrap();
+        let mut resp = replay
+            .send(request(r#"{ "n": 1, "model": "m" }"#))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let mut chunks = Vec::new();
+        while let Some(chunk) = resp.chunk().await {
+            chunks.push(chunk.unwrap());
+        }
+        assert_eq!(
+            chunks,
+            [
+                "data: {\"a\":1}\n\n",
+                "data: {\"a\":2}\n\n",
+                "data: [DONE]\n\n"
+            ]
+        );
+        assert!(replay
+            .send(request(r#"{"model":"m","n":1}"#))
+            .await
+            .is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn redacts_sensitive_values_and_records_body_errors() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", fastrand::u64(..)));
+        let scripted = ScriptedTransport::new();
+        scripted.push(
+            ScriptedResponse::new(200)
+                .chunk("data: {\"a\":1}\n\n")
+                .body_error(TransportErrorKind::Body, "connection reset"),
+        );
+
+        let recorder = CassetteTransport::record(scripted, &path);
+        let mut req = request(r#"{"model":"m"}"#);
+        req.url.set_query(Some("api-version=1&api-key=sk-query"));
+        let mut token = HeaderValue::from_static("sk-custom");
+        token.set_sensitive(true);
+        req.headers.insert("x-gateway-token", token);
+        let resp = recorder.send(req).await.unwrap();
+        assert!(resp.bytes().await.is_err());
+        assert!(!path.exists(), "written while streaming");
+        recorder.save().unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("sk-query") && !saved.contains("sk-custom"));
+        assert!(saved.contains("api-version=1"));
+
+        let replay = CassetteTransport::replay(&path).unwrap();
+        let mut resp = replay.send(request(r#"{"model":"m"}"#)).await.unwrap();
+        assert_eq!(resp.chunk().await.unwrap().unwrap(), "data: {\"a\":1}\n\n");
+        let err = resp.chunk().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("connection reset"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
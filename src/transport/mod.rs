@@ -0,0 +1,197 @@
+//! The HTTP layer under [`crate::OpenAI`].
+//!
+//! The client builds an [`HttpRequest`] per attempt and hands it to an
+//! [`HttpTransport`], which answers with an [`HttpResponse`] whose body is a
+//! stream of byte chunks. [`ReqwestTransport`] is the default (`reqwest`
+//! feature); [`ScriptedTransport`] answers from memory for tests and
+//! [`CassetteTransport`] records and replays real traffic.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt};
+use http::header::HeaderMap;
+use http::{Method, StatusCode};
+use thiserror::Error as ThisError;
+use url::Url;
+
+use crate::error::Error;
+use crate::utils::{BoxFuture, BoxStream};
+
+mod cassette;
+#[cfg(feature = "reqwest")]
+mod reqwest_transport;
+mod scripted;
+
+pub use cassette::CassetteTransport;
+#[cfg(feature = "reqwest")]
+pub use reqwest_transport::ReqwestTransport;
+pub use scripted::{ScriptedResponse, ScriptedTransport};
+
+/// Sends one HTTP request and returns the response as soon as its headers
+/// arrive.
+pub trait HttpTransport: Send + Sync {
+    fn send(&self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>>;
+}
+
+/// Body of an [`HttpResponse`], in the chunks the transport received.
+pub type ByteStream = BoxStream<'static, Result<Bytes, Error>>;
+
+/// A fully built request: URL, headers (auth included) and body.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    /// Empty for requests without a body.
+    pub body: Bytes,
+    /// Timeout for this attempt; the transport's default applies when unset.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: Url) -> Self {
+        Self {
+            method,
+            url,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+            timeout: None,
+        }
+    }
+}
+
+/// A response whose body has not been read yet.
+pub struct HttpResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: ByteStream,
+}
+
+impl std::fmt::Debug for HttpResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpResponse {
+    pub fn new(status: StatusCode, headers: HeaderMap, body: ByteStream) -> Self {
+        Self {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    /// A response with a body that is already in memory.
+    pub fn from_bytes<B: Into<Bytes>>(status: StatusCode, headers: HeaderMap, body: B) -> Self {
+        let body = body.into();
+        let stream = futures_util::stream::iter((!body.is_empty()).then_some(Ok(body)));
+        Self::new(status, headers, Box::pin(stream))
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    /// Replace the body, e.g. to observe or rewrite stream chunks in a
+    /// middleware.
+    pub fn map_body<F: FnOnce(ByteStream) -> ByteStream>(mut self, f: F) -> Self {
+        self.body = f(self.body);
+        self
+    }
+
+    pub fn into_body(self) -> ByteStream {
+        self.body
+    }
+
+    /// Read the whole body.
+    pub async fn bytes(self) -> Result<Bytes, Error> {
+        let chunks: Vec<Bytes> = self.body.try_collect().await?;
+        Ok(match chunks.len() {
+            1 => chunks.into_iter().next().expect("one chunk"),
+            _ => chunks.concat().into(),
+        })
+    }
+
+    /// Read the whole body as UTF-8, replacing invalid sequences.
+    pub async fn text(self) -> Result<String, Error> {
+        let bytes = self.bytes().await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Read the body chunk by chunk.
+    pub async fn chunk(&mut self) -> Option<Result<Bytes, Error>> {
+        self.body.next().await
+    }
+}
+
+/// What kind of failure an [`Error::Transport`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// The connection could not be made; the server never saw the request.
+    Connect,
+    Timeout,
+    /// Sending the request failed after the connection was made.
+    Request,
+    /// Reading the response body failed.
+    Body,
+    Other,
+}
+
+/// Failure of a custom [`HttpTransport`].
+#[derive(Debug, ThisError)]
+#[error("{message}")]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    message: String,
+    #[source]
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl TransportError {
+    pub fn new<S: Into<String>>(kind: TransportErrorKind, message: S) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Wrap the error of an underlying HTTP library.
+    pub fn with_source<E>(kind: TransportErrorKind, source: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self {
+            kind,
+            message: source.to_string(),
+            source: Some(Box::new(source)),
+        }
+    }
+
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+}
+
+/// The transport-level kind of `e`, if it is a transport failure.
+pub(crate) fn transport_kind(e: &Error) -> Option<TransportErrorKind> {
+    match e {
+        #[cfg(feature = "reqwest")]
+        Error::Http(e) => Some(reqwest_transport::kind_of(e)),
+        Error::Transport(e) => Some(e.kind()),
+        _ => None,
+    }
+}
@@ -0,0 +1,67 @@
+use futures_util::TryStreamExt;
+
+use super::{HttpRequest, HttpResponse, HttpTransport, TransportErrorKind};
+use crate::error::Error;
+use crate::utils::BoxFuture;
+
+/// [`HttpTransport`] backed by a [`reqwest::Client`].
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            let mut builder = self
+                .client
+                .request(req.method, req.url)
+                .headers(req.headers);
+            if !req.body.is_empty() {
+                builder = builder.body(req.body);
+            }
+            if let Some(t) = req.timeout {
+                builder = builder.timeout(t);
+            }
+            let resp = builder.send().await?;
+            let status = resp.status();
+            let headers = resp.headers().clone();
+            let body = resp.bytes_stream().map_err(Error::Http);
+            Ok(HttpResponse::new(status, headers, Box::pin(body)))
+        })
+    }
+}
+
+pub(super) fn kind_of(e: &reqwest::Error) -> TransportErrorKind {
+    if e.is_timeout() {
+        TransportErrorKind::Timeout
+    } else if is_connect(e) {
+        TransportErrorKind::Connect
+    } else if e.is_request() {
+        TransportErrorKind::Request
+    } else if e.is_body() || e.is_decode() {
+        TransportErrorKind::Body
+    } else {
+        TransportErrorKind::Other
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn is_connect(e: &reqwest::Error) -> bool {
+    e.is_connect()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn is_connect(_: &reqwest::Error) -> bool {
+    false
+}
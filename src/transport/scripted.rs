@@ -0,0 +1,159 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use http::StatusCode;
+
+use super::{HttpRequest, HttpResponse, HttpTransport, TransportError, TransportErrorKind};
+use crate::error::Error;
+use crate::utils::BoxFuture;
+
+/// In-memory [`HttpTransport`] for tests: answers requests with scripted
+/// responses in order and records every request it receives.
+///
+/// Clones share the script, so keep one to inspect the requests after
+/// handing another to the client builder's `transport` method.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedTransport {
+    inner: Arc<Mutex<Script>>,
+}
+
+#[derive(Debug, Default)]
+struct Script {
+    queue: VecDeque<Result<ScriptedResponse, (TransportErrorKind, String)>>,
+    requests: Vec<HttpRequest>,
+}
+
+impl ScriptedTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer the next unanswered request with `resp`.
+    pub fn push(&self, resp: ScriptedResponse) -> &Self {
+        self.lock().queue.push_back(Ok(resp));
+        self
+    }
+
+    /// Fail the next unanswered request with a transport error.
+    pub fn push_error<S: Into<String>>(&self, kind: TransportErrorKind, message: S) -> &Self {
+        self.lock().queue.push_back(Err((kind, message.into())));
+        self
+    }
+
+    /// Requests received so far, in order.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Scripted answers not used yet.
+    pub fn remaining(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Script> {
+        self.inner.lock().expect("scripted transport lock")
+    }
+}
+
+impl HttpTransport for ScriptedTransport {
+    fn send(&self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        let mut script = self.lock();
+        let next = script.queue.pop_front().unwrap_or_else(|| {
+            let msg = format!("no scripted response for {} {}", req.method, req.url);
+            Err((TransportErrorKind::Other, msg))
+        });
+        script.requests.push(req);
+        drop(script);
+        Box::pin(async move {
+            match next {
+                Ok(resp) => Ok(resp.into_response()),
+                Err((kind, msg)) => Err(TransportError::new(kind, msg).into()),
+            }
+        })
+    }
+}
+
+/// A response for [`ScriptedTransport`], delivered in the given body chunks.
+#[derive(Debug, Clone)]
+pub struct ScriptedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    chunks: Vec<Bytes>,
+    body_error: Option<(TransportErrorKind, String)>,
+}
+
+impl ScriptedResponse {
+    /// An empty response with `status`.
+    ///
+    /// # Panics
+    ///
+    /// If `status` is not a valid HTTP status code.
+    pub fn new(status: u16) -> Self {
+        Self {
+            status: StatusCode::from_u16(status).expect("valid status code"),
+            headers: HeaderMap::new(),
+            chunks: Vec::new(),
+            body_error: None,
+        }
+    }
+
+    /// A JSON response.
+    pub fn json(status: u16, body: &serde_json::Value) -> Self {
+        Self::new(status)
+            .header("content-type", "application/json")
+            .chunk(body.to_string())
+    }
+
+    /// A `200` event stream sending each `data:` payload in its own chunk,
+    /// followed by `data: [DONE]`.
+    pub fn sse<I, S>(events: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let resp = Self::new(200).header("content-type", "text/event-stream");
+        events
+            .into_iter()
+            .fold(resp, |r, data| {
+                r.chunk(format!("data: {}\n\n", data.as_ref()))
+            })
+            .chunk("data: [DONE]\n\n")
+    }
+
+    /// # Panics
+    ///
+    /// If `name` or `value` is not a valid header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        let name = HeaderName::from_bytes(name.as_bytes()).expect("valid header name");
+        let value = HeaderValue::from_str(value).expect("valid header value");
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Append a body chunk.
+    pub fn chunk<B: Into<Bytes>>(mut self, chunk: B) -> Self {
+        self.chunks.push(chunk.into());
+        self
+    }
+
+    /// Fail the body with a transport error after the chunks, like a
+    /// dropped connection.
+    pub fn body_error<S: Into<String>>(mut self, kind: TransportErrorKind, message: S) -> Self {
+        self.body_error = Some((kind, message.into()));
+        self
+    }
+
+    fn into_response(self) -> HttpResponse {
+        let mut headers = self.headers;
+        if !headers.contains_key(CONTENT_TYPE) && !self.chunks.is_empty() {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        }
+        let error = self
+            .body_error
+            .map(|(kind, msg)| Err(TransportError::new(kind, msg).into()));
+        let body = futures_util::stream::iter(self.chunks.into_iter().map(Ok).chain(error));
+        HttpResponse::new(self.status, headers, Box::pin(body))
+    }
+}
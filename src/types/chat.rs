@@ -38,6 +38,8 @@ pub struct ChatCompletionRequest {
     pub tool_choice: Option<ToolChoiceType>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_effort: Option<ReasoningEffort>,
+    #[serde(flatten)]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
 }
 
 impl_builder_methods!(
@@ -57,6 +59,8 @@ impl_builder_methods!(
     reasoning_effort: ReasoningEffort
 );
 
+impl_extra_body!(ChatCompletionRequest);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Stop {
@@ -180,6 +184,9 @@ pub enum ReasoningEffort {
     Low,
     Medium,
     High,
+    /// An effort level added to the API after this version.
+    #[serde(untagged)]
+    Other(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -277,6 +284,9 @@ pub enum Role {
     User,
     Assistant,
     Tool,
+    /// A role added to the API after this version, kept as sent.
+    #[serde(untagged)]
+    Other(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -391,6 +401,18 @@ pub struct ChatCompletionResponse {
     pub created: u64,
     pub model: String,
     pub choices: Vec<ChatChoice>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
+}
+
+impl ChatCompletionResponse {
+    /// Text of the first choice's message, if it is plain text.
+    pub fn first_choice_text(&self) -> Option<&str> {
+        match self.choices.first()?.message.content.as_ref()? {
+            MessageContent::Text { content } => Some(content.as_str()),
+            MessageContent::Blocks { .. } => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -399,6 +421,8 @@ pub struct ChatChoice {
     pub message: ChatMessage,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub finish_reason: Option<String>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 // Streaming chunk types for chat.completions
@@ -409,6 +433,8 @@ pub struct ChatCompletionChunk {
     pub created: u64,
     pub model: String,
     pub choices: Vec<ChatChunkChoice>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -417,6 +443,8 @@ pub struct ChatChunkChoice {
     pub delta: ChatDelta,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub finish_reason: Option<String>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -429,6 +457,8 @@ pub struct ChatDelta {
     pub reasoning_content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<ToolCallDelta>>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -439,6 +469,8 @@ pub struct ToolCallDelta {
     pub r#type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub function: Option<ToolCallFunctionDelta>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -6,6 +6,21 @@ pub struct EmbeddingsRequest {
     pub input: EmbeddingInput,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
+    #[serde(flatten)]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
+}
+
+impl_extra_body!(EmbeddingsRequest);
+
+impl EmbeddingsRequest {
+    pub fn new<M: Into<String>, I: Into<EmbeddingInput>>(model: M, input: I) -> Self {
+        Self {
+            model: model.into(),
+            input: input.into(),
+            user: None,
+            extra_body: Default::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +52,8 @@ pub struct EmbeddingsResponse {
     pub data: Vec<EmbeddingData>,
     pub model: String,
     pub usage: Option<EmbeddingsUsage>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,10 +61,14 @@ pub struct EmbeddingData {
     pub object: String,
     pub index: usize,
     pub embedding: Vec<f32>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingsUsage {
     pub prompt_tokens: u32,
     pub total_tokens: u32,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
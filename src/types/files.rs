@@ -8,12 +8,16 @@ pub struct FileObject {
     pub created_at: u64,
     pub filename: String,
     pub purpose: String,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileListResponse {
     pub object: String,
     pub data: Vec<FileObject>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,4 +25,6 @@ pub struct FileDeleteResponse {
     pub id: String,
     pub object: Option<String>,
     pub deleted: bool,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
@@ -17,12 +17,18 @@ pub struct ImageGenerationRequest {
     pub size: Option<String>, // e.g., "1024x1024"
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response_format: Option<ImageResponseFormat>,
+    #[serde(flatten)]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
 }
 
+impl_extra_body!(ImageGenerationRequest);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageGenerationResponse {
     pub created: u64,
     pub data: Vec<ImageData>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,4 +37,6 @@ pub struct ImageData {
     pub url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub b64_json: Option<String>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
@@ -1,9 +1,41 @@
+// Setters for the flattened `extra_body` map of a request type.
+macro_rules! impl_extra_body {
+    ($request:ident) => {
+        impl $request {
+            /// Add a top-level field to the JSON body, for parameters this type
+            /// does not model (e.g. `top_k` on vLLM, `provider` on OpenRouter).
+            /// It replaces a typed field of the same name.
+            pub fn body_field<K: Into<String>, V: Into<serde_json::Value>>(
+                mut self,
+                name: K,
+                value: V,
+            ) -> Self {
+                self.extra_body.insert(name.into(), value.into());
+                self
+            }
+
+            /// Replace all extra body fields.
+            pub fn extra_body(
+                mut self,
+                fields: serde_json::Map<String, serde_json::Value>,
+            ) -> Self {
+                self.extra_body = fields;
+                self
+            }
+        }
+    };
+}
+
 pub mod chat;
 pub mod embeddings;
 pub mod files;
 pub mod images;
 pub mod responses;
 
+/// Fields a response carried that its type does not model yet. They are kept
+/// as received and serialized back unchanged.
+pub type Extra = serde_json::Map<String, serde_json::Value>;
+
 #[macro_export]
 macro_rules! impl_builder_methods {
     ($builder:ident, $($field:ident: $field_type:ty),*) => {
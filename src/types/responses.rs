@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::ApiErrorBody;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ResponsesRequest {
     pub model: String,
@@ -35,13 +37,19 @@ pub struct ResponsesRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub store: Option<bool>, // Whether to store the conversation
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<bool>, // Run asynchronously; poll or stream by response id
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning: Option<ReasoningConfig>, // Reasoning configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_logprobs: Option<u32>, // Number of most likely tokens to return at each position
     #[serde(skip_serializing_if = "Option::is_none")]
     pub logprobs: Option<bool>, // Whether to return log probabilities
+    #[serde(flatten)]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
 }
 
+impl_extra_body!(ResponsesRequest);
+
 impl ResponsesRequest {
     pub fn text<T: Into<String>>(model: T, input: T) -> Self {
         Self {
@@ -178,6 +186,8 @@ pub struct ResponsesResponse {
     pub output: Option<serde_json::Value>,
     #[serde(default)]
     pub usage: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 impl ResponsesResponse {
@@ -245,6 +255,11 @@ pub struct ResponseStreamEvent {
     pub message: Option<serde_json::Value>,
     #[serde(default)]
     pub usage: Option<serde_json::Value>,
+    /// Position of the event in the response's stream; used to resume.
+    #[serde(default)]
+    pub sequence_number: Option<u64>,
+    #[serde(flatten)]
+    pub extra: super::Extra,
 }
 
 // Tool specification for function calling
@@ -275,7 +290,46 @@ impl super::responses::ResponsesResponse {
     }
 }
 
+/// How a streamed response ended, from its terminal event.
+#[derive(Debug, Clone)]
+pub enum ResponseOutcome {
+    /// `response.completed`.
+    Completed,
+    /// `response.failed`, with the response's `error` object when present.
+    Failed { error: Option<ApiErrorBody> },
+    /// `response.incomplete`, with the reason, e.g. `max_output_tokens` or
+    /// `content_filter`.
+    Incomplete { reason: Option<String> },
+}
+
 impl super::responses::ResponseStreamEvent {
+    /// The outcome carried by a terminal event (`response.completed`,
+    /// `response.failed` or `response.incomplete`); `None` for other events.
+    pub fn outcome(&self) -> Option<ResponseOutcome> {
+        let response = self.response.as_ref();
+        match self.type_.as_str() {
+            "response.completed" => Some(ResponseOutcome::Completed),
+            "response.failed" => Some(ResponseOutcome::Failed {
+                error: response
+                    .and_then(|r| r.get("error"))
+                    .and_then(|e| ApiErrorBody::deserialize(e).ok()),
+            }),
+            "response.incomplete" => Some(ResponseOutcome::Incomplete {
+                reason: response
+                    .and_then(|r| r.pointer("/incomplete_details/reason"))
+                    .and_then(|r| r.as_str())
+                    .map(str::to_string),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Id of the response, carried by lifecycle events such as
+    /// `response.created`.
+    pub fn response_id(&self) -> Option<&str> {
+        self.response.as_ref()?.get("id")?.as_str()
+    }
+
     pub fn function_calls(&self) -> Vec<FunctionCall> {
         let mut out = Vec::new();
         if let Some(v) = &self.response {
@@ -1,11 +1,15 @@
 #[cfg(not(target_arch = "wasm32"))]
 mod native {
+    use core::future::Future;
     use core::pin::Pin;
     use std::time::Duration;
 
     use futures_util::stream::Stream;
 
     pub type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + 'a>>;
+    pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+    pub(crate) use std::time::Instant;
 
     pub async fn sleep(duration: Duration) {
         tokio::time::sleep(duration).await;
@@ -14,6 +18,7 @@ mod native {
 
 #[cfg(target_arch = "wasm32")]
 mod wasm32 {
+    use core::future::Future;
     use core::pin::Pin;
     use std::time::Duration;
 
@@ -21,6 +26,10 @@ mod wasm32 {
     use tokio_with_wasm::alias as tokio;
 
     pub type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + 'a>>;
+    pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+    // `std::time::Instant::now` panics on wasm32-unknown-unknown.
+    pub(crate) use web_time::Instant;
 
     pub async fn sleep(duration: Duration) {
         tokio::time::sleep(duration).await;
@@ -32,3 +41,18 @@ pub use native::*;
 
 #[cfg(target_arch = "wasm32")]
 pub use wasm32::*;
+
+/// Run `fut` for at most `duration`; `None` if it did not finish in time.
+pub(crate) async fn timeout<F: core::future::Future>(
+    duration: std::time::Duration,
+    fut: F,
+) -> Option<F::Output> {
+    use futures_util::future::{select, Either};
+
+    let fut = core::pin::pin!(fut);
+    let timer = core::pin::pin!(sleep(duration));
+    match select(fut, timer).await {
+        Either::Left((out, _)) => Some(out),
+        Either::Right(_) => None,
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "blocking")]
+
+use std::time::Duration;
+
+use openai_sdk_rs::{
+    blocking, RetryPolicy, ScriptedResponse, ScriptedTransport, TransportErrorKind,
+};
+
+mod common;
+use common::request;
+
+#[test]
+fn blocking_client_retries_and_iterates_streams() {
+    let transport = ScriptedTransport::new();
+    transport
+        .push_error(TransportErrorKind::Connect, "connection refused")
+        .push(ScriptedResponse::json(
+            200,
+            &serde_json::json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 0,
+                "model": "gpt-4o-mini",
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": "ok"}}]
+            }),
+        ))
+        .push(ScriptedResponse::sse([
+            r#"{"id":"c","object":"chat.completion.chunk","created":0,"model":"m","choices":[{"index":0,"delta":{"content":"Hel"}}]}"#,
+            r#"{"id":"c","object":"chat.completion.chunk","created":0,"model":"m","choices":[{"index":0,"delta":{"content":"lo"}}]}"#,
+        ]));
+
+    let client: blocking::OpenAI = blocking::OpenAI::builder()
+        .api_key("sk-test".into())
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::new().base_delay(Duration::from_millis(1)))
+        .build_blocking()
+        .unwrap();
+
+    let resp = client.chat_completion(request()).unwrap();
+    assert_eq!(resp.first_choice_text(), Some("ok"));
+    assert_eq!(transport.requests().len(), 2);
+
+    let text: String = client
+        .chat_completion_stream(request())
+        .unwrap()
+        .map(|chunk| {
+            chunk.unwrap().choices[0]
+                .delta
+                .content
+                .clone()
+                .unwrap_or_default()
+        })
+        .collect();
+    assert_eq!(text, "Hello");
+}
+
+#[test]
+fn blocking_client_talks_http() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    // The mock server runs on its own runtime; the blocking client must not.
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/files"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"object": "list", "data": []})),
+            )
+            .mount(&server)
+            .await;
+        server
+    });
+
+    let client = blocking::OpenAI::builder()
+        .api_key("sk-test".into())
+        .base_url(server.uri())
+        .build_blocking()
+        .unwrap();
+    assert!(client.files_list().unwrap().data.is_empty());
+    assert_eq!(
+        client
+            .with_raw_response()
+            .files_list()
+            .unwrap()
+            .meta
+            .request_id,
+        None
+    );
+}
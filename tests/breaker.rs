@@ -0,0 +1,59 @@
+#![cfg(feature = "reqwest")]
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use openai_sdk_rs::{BreakerState, CircuitBreaker, Error, OpenAI, RetryPolicy};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+use common::request_for;
+
+#[tokio::test]
+async fn open_circuit_fails_fast_per_model() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let log = changes.clone();
+    let breaker = CircuitBreaker::new()
+        .failure_threshold(2)
+        .open_for(Duration::from_secs(60))
+        .on_state_change(move |c| log.lock().unwrap().push((c.model.clone(), c.to)));
+    let client = OpenAI::builder()
+        .api_key("sk-test".into())
+        .base_url(server.uri())
+        .retry_policy(RetryPolicy::none())
+        .circuit_breaker(breaker.clone())
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        let err = client.chat_completion(request_for("a")).await.unwrap_err();
+        assert!(matches!(err, Error::UnexpectedStatus { status: 503, .. }));
+    }
+    let err = client.chat_completion(request_for("a")).await.unwrap_err();
+    assert!(matches!(
+        err,
+        Error::CircuitOpen { ref path, ref model, .. }
+            if path == "/v1/chat/completions" && model.as_deref() == Some("a")
+    ));
+    assert_eq!(
+        breaker.state("/v1/chat/completions", Some("a")),
+        BreakerState::Open
+    );
+
+    // Other models keep their own circuit.
+    let err = client.chat_completion(request_for("b")).await.unwrap_err();
+    assert!(matches!(err, Error::UnexpectedStatus { .. }));
+    assert_eq!(
+        *changes.lock().unwrap(),
+        vec![(Some("a".to_string()), BreakerState::Open)]
+    );
+}
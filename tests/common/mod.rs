@@ -0,0 +1,23 @@
+// Fixtures shared by the integration tests; each test crate uses a subset.
+#![allow(dead_code)]
+
+use openai_sdk_rs::types::chat::{ChatCompletionRequest, ChatMessage};
+
+pub fn chat_body() -> serde_json::Value {
+    serde_json::json!({
+        "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4o-mini",
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "ok"}}]
+    })
+}
+
+pub fn request() -> ChatCompletionRequest {
+    request_for("gpt-4o-mini")
+}
+
+pub fn request_for(model: &str) -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: model.into(),
+        messages: vec![ChatMessage::user("hi")],
+        ..Default::default()
+    }
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "reqwest")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use openai_sdk_rs::{BoxFuture, Credential, CredentialProvider, Error, OpenAI};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// Hands out "token-1", "token-2", ... valid for `ttl`.
+struct Rotating {
+    calls: Arc<AtomicUsize>,
+    ttl: Duration,
+}
+
+impl CredentialProvider for Rotating {
+    fn credential(&self) -> BoxFuture<'_, Result<Credential, Error>> {
+        let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let ttl = self.ttl;
+        Box::pin(async move { Ok(Credential::expiring_in(format!("token-{}", n), ttl)) })
+    }
+}
+
+fn list_body() -> serde_json::Value {
+    serde_json::json!({"object": "list", "data": []})
+}
+
+#[tokio::test]
+async fn refreshes_once_after_unauthorized() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/files"))
+        .and(header("authorization", "Bearer token-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(list_body()))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/files"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = OpenAI::builder()
+        .credential_provider(Rotating {
+            calls: calls.clone(),
+            ttl: Duration::from_secs(3600),
+        })
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+
+    client.files_list().await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    // The refreshed token is cached for later calls.
+    client.files_list().await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn static_key_is_not_resent_after_unauthorized() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/files"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("sk-revoked".into())
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+
+    let err = client.files_list().await.unwrap_err();
+    assert_eq!(err.status(), Some(401));
+}
+
+#[tokio::test]
+async fn refreshes_before_expiry() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/files"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(list_body()))
+        .mount(&server)
+        .await;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = OpenAI::builder()
+        .credential_provider(Rotating {
+            calls: calls.clone(),
+            // Shorter than the usual 60s skew: refreshed halfway instead.
+            ttl: Duration::from_millis(400),
+        })
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+
+    client.files_list().await.unwrap();
+    client.files_list().await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    client.files_list().await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "reqwest")]
+
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
@@ -8,7 +10,7 @@ use openai_sdk_rs::{
     },
     OpenAI,
 };
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{body_json, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
@@ -53,12 +55,46 @@ async fn retries_until_success() {
         model: "text-embedding-3-small".into(),
         input: EmbeddingInput::from("hi"),
         user: None,
+        extra_body: Default::default(),
     };
     let resp = client.embeddings(req).await.unwrap();
     assert_eq!(resp.data.len(), 1);
     assert!(ATTEMPTS.load(Ordering::SeqCst) >= 3);
 }
 
+#[tokio::test]
+async fn embeddings_extra_body_reaches_the_wire() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/embeddings"))
+        .and(body_json(serde_json::json!({
+            "model": "text-embedding-3-small",
+            "input": "hi",
+            "dimensions": 256,
+            "encoding_format": "float"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [{"object": "embedding", "index": 0, "embedding": [0.1]}],
+            "model": "text-embedding-3-small",
+            "usage": {"prompt_tokens": 1, "total_tokens": 1}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("api_key".into())
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+    let req = EmbeddingsRequest::new("text-embedding-3-small", "hi")
+        .body_field("dimensions", 256)
+        .body_field("encoding_format", "float");
+    let resp = client.embeddings(req).await.unwrap();
+    assert_eq!(resp.data.len(), 1);
+}
+
 #[tokio::test]
 async fn sse_streaming_responses() {
     let server = MockServer::start().await;
@@ -93,3 +129,85 @@ data: [DONE]\n";
         Err(e) => panic!("Failed to stream responses: {}", e),
     }
 }
+
+#[tokio::test]
+async fn azure_routes_to_deployment() {
+    use openai_sdk_rs::{types::chat::ChatCompletionRequest, AzureConfig};
+    use wiremock::matchers::{header, query_param};
+
+    let server = MockServer::start().await;
+    let body = serde_json::json!({
+        "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4o",
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "hi"}, "finish_reason": "stop"}]
+    });
+    Mock::given(method("POST"))
+        .and(path("/openai/deployments/prod-4o/chat/completions"))
+        .and(query_param("api-version", "2024-10-21"))
+        .and(header("api-key", "azure-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("azure-key".into())
+        .base_url(server.uri())
+        .azure(AzureConfig::new("2024-10-21").deployment("gpt-4o", "prod-4o"))
+        .build()
+        .unwrap();
+    let req = ChatCompletionRequest {
+        model: "gpt-4o".into(),
+        ..Default::default()
+    };
+    let resp = client.chat_completion(req).await.unwrap();
+    assert_eq!(resp.first_choice_text(), Some("hi"));
+}
+
+#[tokio::test]
+async fn raw_response_exposes_headers_and_error_request_id() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/files"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"object": "list", "data": []}))
+                .insert_header("x-request-id", "req_123")
+                .insert_header("openai-processing-ms", "42")
+                .insert_header("x-ratelimit-remaining-requests", "59")
+                .insert_header("x-ratelimit-reset-tokens", "6m0s"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/v1/files/file-missing"))
+        .respond_with(
+            ResponseTemplate::new(404)
+                .set_body_json(serde_json::json!({"error": {"message": "No such file", "type": "invalid_request_error", "param": null, "code": null}}))
+                .insert_header("x-request-id", "req_404"),
+        )
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("api_key".into())
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+
+    let raw = client.with_raw_response().files_list().await.unwrap();
+    assert_eq!(raw.meta.request_id.as_deref(), Some("req_123"));
+    assert_eq!(raw.meta.processing_time, Some(Duration::from_millis(42)));
+    assert_eq!(raw.meta.rate_limit.remaining_requests, Some(59));
+    assert_eq!(
+        raw.meta.rate_limit.reset_tokens,
+        Some(Duration::from_secs(360))
+    );
+    assert!(raw.data.data.is_empty());
+
+    match client.files_delete("file-missing").await {
+        Err(openai_sdk_rs::Error::Api(e)) => {
+            assert_eq!(e.status, Some(404));
+            assert_eq!(e.request_id.as_deref(), Some("req_404"));
+        }
+        other => panic!("unexpected: {:?}", other),
+    }
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "reqwest")]
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use openai_sdk_rs::{metrics, MetricsSink, OpenAI, RetryPolicy};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+use common::request;
+
+type Samples = Arc<Mutex<Vec<(&'static str, f64, Vec<(&'static str, String)>)>>>;
+
+#[derive(Default, Clone)]
+struct Recorder(Samples);
+
+impl Recorder {
+    fn sum(&self, name: &str, label: (&str, &str)) -> f64 {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(n, _, labels)| {
+                *n == name && labels.iter().any(|(k, v)| *k == label.0 && v == label.1)
+            })
+            .map(|(_, value, _)| value)
+            .sum()
+    }
+}
+
+impl MetricsSink for Recorder {
+    fn counter(&self, name: &'static str, value: u64, labels: &[(&'static str, &str)]) {
+        self.histogram(name, value as f64, labels);
+    }
+
+    fn histogram(&self, name: &'static str, value: f64, labels: &[(&'static str, &str)]) {
+        let labels = labels.iter().map(|(k, v)| (*k, v.to_string())).collect();
+        self.0.lock().unwrap().push((name, value, labels));
+    }
+}
+
+#[tokio::test]
+async fn records_requests_errors_retries_and_tokens() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(serde_json::json!({"stream": true})))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(concat!(
+                    "data: {\"id\":\"c\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o-mini\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"hi\"}}],\"usage\":null}\n\n",
+                    "data: {\"id\":\"c\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o-mini\",\"choices\":[],\"usage\":{\"prompt_tokens\":4,\"completion_tokens\":1,\"total_tokens\":5}}\n\n",
+                    "data: [DONE]\n\n"
+                ))
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(429).set_body_json(serde_json::json!({
+            "error": {"message": "slow down", "type": "requests", "param": null, "code": "rate_limit_exceeded"}
+        })))
+        .mount(&server)
+        .await;
+
+    let recorder = Recorder::default();
+    let client = OpenAI::builder()
+        .api_key("sk-test".into())
+        .base_url(server.uri())
+        .retry_policy(
+            RetryPolicy::new()
+                .max_retries(2)
+                .base_delay(Duration::from_millis(1)),
+        )
+        .metrics(recorder.clone())
+        .build()
+        .unwrap();
+
+    client.chat_completion(request()).await.unwrap_err();
+    assert_eq!(
+        recorder.sum(metrics::RETRIES, ("model", "gpt-4o-mini")),
+        2.0
+    );
+    assert_eq!(recorder.sum(metrics::REQUESTS, ("status", "429")), 1.0);
+    assert_eq!(
+        recorder.sum(metrics::ERRORS, ("code", "rate_limit_exceeded")),
+        1.0
+    );
+
+    let mut stream = client.chat_completion_stream(request()).await.unwrap();
+    while let Some(chunk) = stream.next().await {
+        chunk.unwrap();
+    }
+    assert_eq!(recorder.sum(metrics::REQUESTS, ("status", "200")), 1.0);
+    assert_eq!(
+        recorder.sum(metrics::INPUT_TOKENS, ("model", "gpt-4o-mini")),
+        4.0
+    );
+    assert_eq!(
+        recorder.sum(metrics::OUTPUT_TOKENS, ("model", "gpt-4o-mini")),
+        1.0
+    );
+    assert!(recorder.sum(metrics::DURATION, ("endpoint", "/v1/chat/completions")) > 0.0);
+}
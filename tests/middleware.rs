@@ -0,0 +1,89 @@
+#![cfg(feature = "reqwest")]
+
+use std::sync::{Arc, Mutex};
+
+use openai_sdk_rs::{
+    types::responses::ResponsesRequest, BoxFuture, Error, HttpRequest, HttpResponse, Middleware,
+    Next, OpenAI,
+};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// Adds a signature header to outgoing requests.
+struct Sign;
+
+impl Middleware for Sign {
+    fn handle<'a>(
+        &'a self,
+        mut req: HttpRequest,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Error>> {
+        req.headers.insert("x-signature", "signed".parse().unwrap());
+        next.run(req)
+    }
+}
+
+// Records "<method> <path> -> <status>" for every attempt.
+struct Audit(Arc<Mutex<Vec<String>>>);
+
+impl Middleware for Audit {
+    fn handle<'a>(
+        &'a self,
+        req: HttpRequest,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            let line = format!("{} {}", req.method, req.url.path());
+            let resp = next.run(req).await?;
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{} -> {}", line, resp.status().as_u16()));
+            Ok(resp)
+        })
+    }
+}
+
+#[tokio::test]
+async fn middleware_runs_for_json_and_sse() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/files"))
+        .and(header("x-signature", "signed"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"object": "list", "data": []})),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(header("x-signature", "signed"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("data: {\"type\":\"content\",\"output_text\":\"hi\"}\n\n")
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&server)
+        .await;
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let client = OpenAI::builder()
+        .api_key("api_key".into())
+        .base_url(server.uri())
+        .middleware(Audit(log.clone()))
+        .middleware(Sign)
+        .build()
+        .unwrap();
+
+    client.files_list().await.unwrap();
+    let text = client
+        .responses_stream_text(ResponsesRequest::text("gpt-4o-mini", "hi"))
+        .await
+        .unwrap();
+    assert_eq!(text, "hi");
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["GET /v1/files -> 200", "POST /v1/responses -> 200"]
+    );
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "reqwest")]
+
+use std::time::Duration;
+
+use openai_sdk_rs::{ErrorKind, OpenAI, RequestOptions, RetryPolicy};
+use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+use common::{chat_body, request};
+
+#[tokio::test]
+async fn options_apply_headers_query_body_and_auth() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(query_param("trace", "1"))
+        .and(header("x-trace-id", "abc"))
+        .and(header("authorization", "Bearer per-call-key"))
+        .and(header("openai-project", "proj_override"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "gpt-4o-mini",
+            "top_k": 20,
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(chat_body()))
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("client-key".into())
+        .project("proj_default")
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+    let opts = RequestOptions::new()
+        .header("x-trace-id", "abc")
+        .query("trace", "1")
+        .body_field("top_k", 20)
+        .project("proj_override".into())
+        .api_key("per-call-key".into());
+    let resp = client
+        .chat_completion_with_options(request(), opts)
+        .await
+        .unwrap();
+    assert_eq!(resp.first_choice_text(), Some("ok"));
+}
+
+#[tokio::test]
+async fn injected_client_keeps_its_own_timeout() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(chat_body())
+                .set_delay(Duration::from_millis(300)),
+        )
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("client-key".into())
+        .base_url(server.uri())
+        .http_client(reqwest::Client::new())
+        .timeout(Duration::from_millis(50))
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap();
+    client.chat_completion(request()).await.unwrap();
+}
+
+#[tokio::test]
+async fn per_call_timeout_overrides_client() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(chat_body())
+                .set_delay(Duration::from_millis(300)),
+        )
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("client-key".into())
+        .base_url(server.uri())
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap();
+    let opts = RequestOptions::new().timeout(Duration::from_millis(50));
+    let err = client
+        .chat_completion_with_options(request(), opts)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Timeout);
+    client.chat_completion(request()).await.unwrap();
+}
+
+#[tokio::test]
+async fn base_path_prefix_and_default_query_are_kept() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/gateway/openai/v1/chat/completions"))
+        .and(query_param("tenant", "acme"))
+        .and(query_param("trace", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(chat_body()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/gateway/openai/v1/files"))
+        .and(query_param("tenant", "other"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"object": "list", "data": []})),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("sk-test".into())
+        .base_url(format!("{}/gateway/openai/", server.uri()))
+        .default_query("tenant", "acme")
+        .build()
+        .unwrap();
+    let opts = RequestOptions::new().query("trace", "1");
+    client
+        .chat_completion_with_options(request(), opts)
+        .await
+        .unwrap();
+    let opts = RequestOptions::new().query("tenant", "other");
+    client.files_list_with_options(opts).await.unwrap();
+}
+
+#[tokio::test]
+async fn stream_helpers_take_options() {
+    use futures_util::StreamExt;
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("x-trace-id", "abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "data: {\"id\":\"c\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"m\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"ok\"}}]}\n\ndata: [DONE]\n\n",
+            "text/event-stream",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/responses/resp_1"))
+        .and(query_param("starting_after", "3"))
+        .and(header("x-trace-id", "abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "data: {\"type\":\"response.output_text.delta\",\"sequence_number\":4,\"delta\":\"!\"}\n\n",
+            "text/event-stream",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("client-key".into())
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+    let opts = RequestOptions::new().header("x-trace-id", "abc");
+    let text = client
+        .chat_completion_stream_text_with_options(request(), opts.clone())
+        .await
+        .unwrap();
+    assert_eq!(text, "ok");
+    let mut events = client
+        .responses_stream_resume_with_options("resp_1", Some(3), opts)
+        .await
+        .unwrap();
+    let ev = events.next().await.unwrap().unwrap();
+    assert_eq!(ev.sequence_number, Some(4));
+}
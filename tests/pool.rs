@@ -0,0 +1,104 @@
+#![cfg(feature = "reqwest")]
+
+use futures_util::StreamExt;
+use openai_sdk_rs::{types::responses::ResponsesRequest, Error, OpenAI, OpenAIPool, RetryPolicy};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+use common::{chat_body, request};
+
+fn backend(server: &MockServer) -> OpenAI {
+    OpenAI::builder()
+        .api_key("sk-test".into())
+        .base_url(server.uri())
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn fails_over_and_marks_backend_unhealthy() {
+    let broken = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&broken)
+        .await;
+    let healthy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(chat_body()))
+        .mount(&healthy)
+        .await;
+
+    let pool = OpenAIPool::builder()
+        .backend(backend(&broken))
+        .backend(backend(&healthy))
+        .unhealthy_after(2)
+        .build()
+        .unwrap();
+    for _ in 0..6 {
+        let resp = pool.chat_completion(request()).await.unwrap();
+        assert_eq!(resp.first_choice_text(), Some("ok"));
+    }
+    assert!(!pool.is_healthy(0));
+    assert!(pool.is_healthy(1));
+}
+
+#[tokio::test]
+async fn streams_fail_over_before_first_byte() {
+    let limited = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(429))
+        .mount(&limited)
+        .await;
+    let healthy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("data: {\"type\":\"content\",\"output_text\":\"hi\"}\n\n")
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&healthy)
+        .await;
+
+    let pool = OpenAIPool::builder()
+        .backend(backend(&limited))
+        .backend(backend(&healthy))
+        .build()
+        .unwrap();
+    let mut stream = pool
+        .responses_stream(ResponsesRequest::text("gpt-4o-mini", "hi"))
+        .await
+        .unwrap();
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.output_text.as_deref(), Some("hi"));
+}
+
+#[tokio::test]
+async fn client_errors_are_not_failed_over() {
+    let bad_request = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": {"message": "bad", "type": "invalid_request_error", "param": null, "code": null}
+        })))
+        .mount(&bad_request)
+        .await;
+    let other = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(chat_body()))
+        .expect(0)
+        .mount(&other)
+        .await;
+
+    let pool = OpenAIPool::builder()
+        .backend(backend(&bad_request))
+        .backend(backend(&other))
+        .build()
+        .unwrap();
+    let err = pool.chat_completion(request()).await.unwrap_err();
+    assert!(matches!(err, Error::Api(e) if e.status == Some(400)));
+    assert!(pool.is_healthy(0));
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "reqwest")]
+
+use std::time::{Duration, Instant};
+
+use openai_sdk_rs::{OpenAI, RateLimit, RateLimiter};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+use common::request;
+
+// The shared body plus the usage the limiter reconciles against.
+fn chat_body() -> serde_json::Value {
+    let mut body = common::chat_body();
+    body["usage"] =
+        serde_json::json!({"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2});
+    body
+}
+
+#[tokio::test]
+async fn waits_when_server_reports_no_budget_left() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(chat_body())
+                .insert_header("x-ratelimit-remaining-requests", "0"),
+        )
+        .mount(&server)
+        .await;
+
+    // 600 RPM refills one request every 100ms.
+    let limiter = RateLimiter::new(RateLimit::requests_per_minute(600));
+    let client = OpenAI::builder()
+        .api_key("sk-test".into())
+        .base_url(server.uri())
+        .rate_limiter(limiter)
+        .build()
+        .unwrap();
+
+    client.chat_completion(request()).await.unwrap();
+    let start = Instant::now();
+    client.chat_completion(request()).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(80));
+}
+
+#[tokio::test]
+async fn token_budget_is_corrected_by_usage() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(chat_body()))
+        .mount(&server)
+        .await;
+
+    // Each call reserves ~500 tokens but reports 2 used, so the 1000 TPM
+    // budget would otherwise run out on the third call.
+    let limiter = RateLimiter::new(RateLimit::tokens_per_minute(1000));
+    let client = OpenAI::builder()
+        .api_key("sk-test".into())
+        .base_url(server.uri())
+        .rate_limiter(limiter)
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        let mut req = request();
+        req.max_completion_tokens = Some(500);
+        client.chat_completion(req).await.unwrap();
+    }
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -41,3 +41,198 @@ fn output_json_parses_text() {
     let j = resp.output_json().unwrap();
     assert_eq!(j["ok"], true);
 }
+
+mod resume {
+    use futures_util::StreamExt;
+    use openai_sdk_rs::{
+        types::responses::ResponsesRequest, Error, OpenAI, ScriptedResponse, ScriptedTransport,
+        TransportErrorKind,
+    };
+
+    fn event(seq: u64, delta: &str) -> String {
+        serde_json::json!({
+            "type": "response.output_text.delta", "sequence_number": seq, "delta": delta
+        })
+        .to_string()
+    }
+
+    fn created() -> String {
+        serde_json::json!({
+            "type": "response.created", "sequence_number": 0,
+            "response": {"id": "resp_1", "status": "in_progress"}
+        })
+        .to_string()
+    }
+
+    fn client(transport: &ScriptedTransport) -> OpenAI {
+        OpenAI::builder()
+            .api_key("sk-test".into())
+            .transport(transport.clone())
+            .build()
+            .unwrap()
+    }
+
+    async fn deltas(client: &OpenAI, req: ResponsesRequest) -> Result<String, Error> {
+        let mut stream = client.responses_stream(req).await?;
+        let mut out = String::new();
+        while let Some(ev) = stream.next().await {
+            if let Some(d) = ev?.delta.as_ref().and_then(|d| d.as_str()) {
+                out.push_str(d);
+            }
+        }
+        Ok(out)
+    }
+
+    #[tokio::test]
+    async fn resumes_after_last_sequence_number_without_duplicates() {
+        let transport = ScriptedTransport::new();
+        transport
+            .push(
+                ScriptedResponse::new(200)
+                    .header("content-type", "text/event-stream")
+                    .chunk(format!("data: {}\n\n", created()))
+                    .chunk(format!("data: {}\n\n", event(1, "Hel")))
+                    .body_error(TransportErrorKind::Body, "connection reset"),
+            )
+            // The server replays event 1 once more; it must be skipped.
+            .push(ScriptedResponse::sse([event(1, "Hel"), event(2, "lo")]));
+
+        let mut req = ResponsesRequest::text("gpt-4o-mini", "hi");
+        req.background = Some(true);
+        assert_eq!(deltas(&client(&transport), req).await.unwrap(), "Hello");
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, http::Method::GET);
+        assert_eq!(requests[1].url.path(), "/v1/responses/resp_1");
+        assert_eq!(
+            requests[1].url.query(),
+            Some("stream=true&starting_after=1")
+        );
+    }
+
+    #[tokio::test]
+    async fn unstored_response_cannot_resume() {
+        let transport = ScriptedTransport::new();
+        transport.push(
+            ScriptedResponse::new(200)
+                .header("content-type", "text/event-stream")
+                .chunk(format!("data: {}\n\n", created()))
+                .body_error(TransportErrorKind::Body, "connection reset"),
+        );
+
+        let mut req = ResponsesRequest::text("gpt-4o-mini", "hi");
+        req.store = Some(false);
+        let err = deltas(&client(&transport), req).await.unwrap_err();
+        assert!(matches!(err, Error::StreamNotResumable { .. }), "{err:?}");
+        assert_eq!(transport.requests().len(), 1);
+    }
+}
+
+mod stream_errors {
+    use futures_util::StreamExt;
+    use openai_sdk_rs::{
+        types::chat::{ChatCompletionRequest, ChatMessage},
+        types::responses::{ResponseOutcome, ResponsesRequest},
+        Error, OpenAI, ScriptedResponse, ScriptedTransport,
+    };
+
+    fn client(transport: &ScriptedTransport) -> OpenAI {
+        OpenAI::builder()
+            .api_key("sk-test".into())
+            .transport(transport.clone())
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn error_payloads_become_api_errors() {
+        let transport = ScriptedTransport::new();
+        transport
+            .push(
+                ScriptedResponse::sse([
+                    r#"{"id":"c","object":"chat.completion.chunk","created":0,"model":"m","choices":[{"index":0,"delta":{"content":"Hi"}}]}"#,
+                    r#"{"error":{"message":"overloaded","type":"server_error","param":null,"code":"overloaded"}}"#,
+                ])
+                .header("x-request-id", "req_1"),
+            )
+            .push(
+                ScriptedResponse::new(200)
+                    .header("content-type", "text/event-stream")
+                    .chunk("event: error\ndata: {\"type\":\"error\",\"code\":\"rate_limit_exceeded\",\"message\":\"slow down\",\"param\":null}\n\n"),
+            );
+        let client = client(&transport);
+
+        let req = ChatCompletionRequest {
+            model: "gpt-4o-mini".into(),
+            messages: vec![ChatMessage::user("hi")],
+            ..Default::default()
+        };
+        let mut stream = client.chat_completion_stream(req).await.unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+        match stream.next().await {
+            Some(Err(Error::Api(api))) => {
+                assert_eq!(api.message, "overloaded");
+                assert_eq!(api.code.as_deref(), Some("overloaded"));
+                assert_eq!(api.request_id.as_deref(), Some("req_1"));
+            }
+            other => panic!("expected api error, got {other:?}"),
+        }
+
+        let mut stream = client
+            .responses_stream(ResponsesRequest::text("gpt-4o-mini", "hi"))
+            .await
+            .unwrap();
+        match stream.next().await {
+            Some(Err(Error::Api(api))) => {
+                assert_eq!(api.message, "slow down");
+                assert_eq!(api.code.as_deref(), Some("rate_limit_exceeded"));
+            }
+            other => panic!("expected api error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_and_incomplete_responses_are_typed() {
+        let failed = r#"{"type":"response.failed","sequence_number":1,"response":{"id":"resp_1","status":"failed","error":{"code":"server_error","message":"boom"}}}"#;
+        let incomplete = r#"{"type":"response.incomplete","sequence_number":1,"response":{"id":"resp_2","status":"incomplete","incomplete_details":{"reason":"max_output_tokens"}}}"#;
+        let transport = ScriptedTransport::new();
+        transport
+            .push(ScriptedResponse::sse([failed]))
+            .push(ScriptedResponse::sse([incomplete]))
+            .push(ScriptedResponse::sse([failed]));
+        let client = client(&transport);
+
+        let mut stream = client
+            .responses_stream(ResponsesRequest::text("gpt-4o-mini", "hi"))
+            .await
+            .unwrap();
+        match stream.next().await.unwrap().unwrap().outcome() {
+            Some(ResponseOutcome::Failed { error: Some(error) }) => {
+                assert_eq!(error.message, "boom");
+                assert_eq!(error.code.as_deref(), Some("server_error"));
+            }
+            other => panic!("expected failed outcome, got {other:?}"),
+        }
+
+        let mut stream = client
+            .responses_stream(ResponsesRequest::text("gpt-4o-mini", "hi"))
+            .await
+            .unwrap();
+        match stream.next().await.unwrap().unwrap().outcome() {
+            Some(ResponseOutcome::Incomplete { reason }) => {
+                assert_eq!(reason.as_deref(), Some("max_output_tokens"));
+            }
+            other => panic!("expected incomplete outcome, got {other:?}"),
+        }
+
+        let err = client
+            .responses_stream_text(ResponsesRequest::text("gpt-4o-mini", "hi"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::Api(ref api) if api.message == "boom"),
+            "{err:?}"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "reqwest")]
+
 use std::time::Duration;
 
 use openai_sdk_rs::OpenAI;
@@ -18,3 +20,102 @@ async fn retry_delay_grows_exponentially() {
     let _ = client;
     // compile-time check
 }
+
+#[tokio::test]
+async fn upload_not_retried_on_500_but_server_can_override() {
+    use openai_sdk_rs::RetryPolicy;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/files"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/files"))
+        .respond_with(ResponseTemplate::new(429).insert_header("x-should-retry", "false"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("api_key".into())
+        .base_url(server.uri())
+        .retry_policy(RetryPolicy::new().base_delay(Duration::from_millis(1)))
+        .build()
+        .unwrap();
+
+    let err = client
+        .files_upload_bytes("a.jsonl", b"{}".to_vec(), "batch")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        openai_sdk_rs::Error::UnexpectedStatus { status: 500, .. }
+    ));
+    assert!(client.files_list().await.is_err());
+}
+
+#[tokio::test]
+async fn retries_reuse_one_idempotency_key() {
+    use openai_sdk_rs::{types::images::ImageGenerationRequest, RequestOptions};
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/images/generations"))
+        .and(header("idempotency-key", "img-42"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/images/generations"))
+        .and(header("idempotency-key", "img-42"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"created": 0, "data": []})),
+        )
+        .mount(&server)
+        .await;
+
+    let client = OpenAI::builder()
+        .api_key("api_key".into())
+        .base_url(server.uri())
+        .retry_base_delay(Duration::from_millis(1))
+        .build()
+        .unwrap();
+    let req = ImageGenerationRequest {
+        model: "dall-e-3".into(),
+        prompt: "a cat".into(),
+        ..Default::default()
+    };
+    client
+        .images_generate_with_options(
+            req.clone(),
+            RequestOptions::new().idempotency_key("img-42".into()),
+        )
+        .await
+        .unwrap();
+
+    // Without a caller key, every attempt of one call shares a generated key.
+    server.reset().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/images/generations"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    assert!(client.images_generate(req).await.is_err());
+    let keys: Vec<String> = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.headers["idempotency-key"].to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(keys.len(), 4);
+    assert!(keys.iter().all(|k| k == &keys[0]));
+}
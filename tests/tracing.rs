@@ -0,0 +1,141 @@
+#![cfg(feature = "tracing")]
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use openai_sdk_rs::{
+    types::chat::{ChatCompletionRequest, ChatMessage},
+    OpenAI, RetryPolicy,
+};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+type Spans = Arc<Mutex<Vec<(String, BTreeMap<String, String>)>>>;
+
+// Collects every span with its fields, including ones recorded later.
+// Span ids are reused once closed, so the latest span of an id wins.
+struct Capture(Spans, Mutex<HashMap<u64, usize>>);
+
+struct Fields<'a>(&'a mut BTreeMap<String, String>);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(
+            field.name().to_string(),
+            format!("{:?}", value).replace('"', ""),
+        );
+    }
+}
+
+impl<S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>> Layer<S>
+    for Capture
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _: Context<'_, S>) {
+        let mut fields = BTreeMap::new();
+        attrs.record(&mut Fields(&mut fields));
+        let mut spans = self.0.lock().unwrap();
+        self.1.lock().unwrap().insert(id.into_u64(), spans.len());
+        spans.push((attrs.metadata().name().to_string(), fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _: Context<'_, S>) {
+        let mut spans = self.0.lock().unwrap();
+        let idx = self.1.lock().unwrap()[&id.into_u64()];
+        values.record(&mut Fields(&mut spans[idx].1));
+    }
+}
+
+#[tokio::test]
+async fn spans_carry_gen_ai_attributes() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4o-mini",
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": "ok"}}],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7}
+        })))
+        .mount(&server)
+        .await;
+
+    let spans = Spans::default();
+    let subscriber = tracing_subscriber::registry().with(Capture(spans.clone(), Mutex::default()));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let client = OpenAI::builder()
+        .api_key("sk-test".into())
+        .base_url(server.uri())
+        .retry_policy(RetryPolicy::new().base_delay(std::time::Duration::from_millis(1)))
+        .build()
+        .unwrap();
+    let req = ChatCompletionRequest {
+        model: "gpt-4o-mini".into(),
+        messages: vec![ChatMessage::user("hi")],
+        ..Default::default()
+    };
+    client.chat_completion(req).await.unwrap();
+
+    let spans = spans.lock().unwrap();
+    let (name, call) = &spans[0];
+    assert_eq!(name, "gen_ai.call");
+    assert_eq!(call["otel.name"], "chat gpt-4o-mini");
+    assert_eq!(call["gen_ai.operation.name"], "chat");
+    assert_eq!(call["gen_ai.request.model"], "gpt-4o-mini");
+    assert_eq!(call["gen_ai.response.model"], "gpt-4o-mini");
+    assert_eq!(call["gen_ai.provider.name"], "openai");
+    assert_eq!(call["server.address"], "127.0.0.1");
+    assert_eq!(call["gen_ai.usage.input_tokens"], "5");
+    assert_eq!(call["gen_ai.usage.output_tokens"], "2");
+    assert_eq!(call["http.response.status_code"], "200");
+    assert_eq!(call["http.request.resend_count"], "1");
+
+    let attempts: Vec<_> = spans.iter().filter(|(n, _)| n == "http.attempt").collect();
+    assert_eq!(attempts.len(), 2);
+    assert_eq!(attempts[0].1["http.response.status_code"], "503");
+}
+
+#[tokio::test]
+async fn stream_spans_carry_time_to_first_chunk() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "data: {\"id\":\"c\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o-mini\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"ok\"}}]}\n\ndata: [DONE]\n\n",
+            "text/event-stream",
+        ))
+        .mount(&server)
+        .await;
+
+    let spans = Spans::default();
+    let subscriber = tracing_subscriber::registry().with(Capture(spans.clone(), Mutex::default()));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let client = OpenAI::builder()
+        .api_key("sk-test".into())
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+    let req = ChatCompletionRequest {
+        model: "gpt-4o-mini".into(),
+        messages: vec![ChatMessage::user("hi")],
+        ..Default::default()
+    };
+    assert_eq!(client.chat_completion_stream_text(req).await.unwrap(), "ok");
+
+    let spans = spans.lock().unwrap();
+    let (name, call) = &spans[0];
+    assert_eq!(name, "gen_ai.call");
+    assert_eq!(call["gen_ai.response.model"], "gpt-4o-mini");
+    let ttfc: f64 = call["gen_ai.response.time_to_first_chunk"].parse().unwrap();
+    assert!(ttfc >= 0.0);
+}
@@ -0,0 +1,202 @@
+use futures_util::StreamExt;
+use openai_sdk_rs::{OpenAI, RetryPolicy, ScriptedResponse, ScriptedTransport, TransportErrorKind};
+use std::time::Duration;
+
+mod common;
+use common::request;
+
+fn client(transport: &ScriptedTransport) -> OpenAI {
+    OpenAI::builder()
+        .api_key("sk-test".into())
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::new().base_delay(Duration::from_millis(1)))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn scripted_transport_serves_json_and_retries_connect_errors() {
+    let transport = ScriptedTransport::new();
+    transport
+        .push_error(TransportErrorKind::Connect, "connection refused")
+        .push(ScriptedResponse::json(
+            200,
+            &serde_json::json!({
+                "id": "chatcmpl-1", "object": "chat.completion", "created": 0,
+                "model": "gpt-4o-mini",
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": "ok"}}]
+            }),
+        ));
+
+    let resp = client(&transport).chat_completion(request()).await.unwrap();
+    assert_eq!(resp.first_choice_text(), Some("ok"));
+    assert_eq!(transport.remaining(), 0);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    let req = &requests[1];
+    assert_eq!(req.method, http::Method::POST);
+    assert_eq!(req.url.path(), "/v1/chat/completions");
+    assert_eq!(req.headers["authorization"], "Bearer sk-test");
+    assert!(req.headers["authorization"].is_sensitive());
+    assert!(!format!("{req:?}").contains("sk-test"));
+    assert_eq!(req.headers["content-type"], "application/json");
+    assert_eq!(
+        requests[0].headers["idempotency-key"],
+        req.headers["idempotency-key"]
+    );
+    let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+    assert_eq!(body["model"], "gpt-4o-mini");
+}
+
+#[tokio::test]
+async fn scripted_transport_streams_sse_chunks() {
+    let transport = ScriptedTransport::new();
+    transport.push(ScriptedResponse::sse([
+        r#"{"id":"c","object":"chat.completion.chunk","created":0,"model":"m","choices":[{"index":0,"delta":{"content":"Hel"}}]}"#,
+        r#"{"id":"c","object":"chat.completion.chunk","created":0,"model":"m","choices":[{"index":0,"delta":{"content":"lo"}}]}"#,
+    ]));
+
+    let mut stream = client(&transport)
+        .chat_completion_stream(request())
+        .await
+        .unwrap();
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.unwrap();
+        if let Some(delta) = chunk.choices[0].delta.content.as_deref() {
+            text.push_str(delta);
+        }
+    }
+    assert_eq!(text, "Hello");
+    assert_eq!(
+        transport.requests()[0].headers["accept"],
+        "text/event-stream"
+    );
+}
+
+#[cfg(feature = "reqwest")]
+#[tokio::test]
+async fn cassette_records_through_the_client_and_replays_offline() {
+    use openai_sdk_rs::{CassetteTransport, ReqwestTransport};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(concat!(
+                    r#"data: {"id":"c","object":"chat.completion.chunk","created":0,"model":"m","choices":[{"index":0,"delta":{"content":"Hi"}}]}"#,
+                    "\n\ndata: [DONE]\n\n"
+                )),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let file = std::env::temp_dir().join(format!("cassette-it-{}.json", std::process::id()));
+    let recorder = CassetteTransport::record(ReqwestTransport::default(), &file);
+    let live = OpenAI::builder()
+        .api_key("sk-live-secret".into())
+        .base_url(server.uri())
+        .transport(recorder.clone())
+        .build()
+        .unwrap();
+    assert_eq!(
+        live.chat_completion_stream_text(request()).await.unwrap(),
+        "Hi"
+    );
+    recorder.save().unwrap();
+    assert!(!std::fs::read_to_string(&file)
+        .unwrap()
+        .contains("sk-live-secret"));
+
+    let offline = OpenAI::builder()
+        .api_key("sk-other".into())
+        .transport(CassetteTransport::replay(&file).unwrap())
+        .build()
+        .unwrap();
+    assert_eq!(
+        offline
+            .chat_completion_stream_text(request())
+            .await
+            .unwrap(),
+        "Hi"
+    );
+    std::fs::remove_file(&file).unwrap();
+}
+
+// Sends one SSE chunk, then nothing, after waiting `delay` for headers.
+struct Stalling {
+    delay: Duration,
+}
+
+impl openai_sdk_rs::HttpTransport for Stalling {
+    fn send(
+        &self,
+        _req: openai_sdk_rs::HttpRequest,
+    ) -> openai_sdk_rs::BoxFuture<'_, Result<openai_sdk_rs::HttpResponse, openai_sdk_rs::Error>>
+    {
+        Box::pin(async move {
+            tokio::time::sleep(self.delay).await;
+            let first = bytes::Bytes::from_static(
+                br#"data: {"id":"c","object":"chat.completion.chunk","created":0,"model":"m","choices":[{"index":0,"delta":{"content":"Hi"}}]}
+
+"#,
+            );
+            let body =
+                futures_util::stream::iter([Ok(first)]).chain(futures_util::stream::pending());
+            Ok(openai_sdk_rs::HttpResponse::new(
+                http::StatusCode::OK,
+                http::HeaderMap::new(),
+                Box::pin(body),
+            ))
+        })
+    }
+}
+
+#[tokio::test]
+async fn stream_timeouts_end_the_stream() {
+    use openai_sdk_rs::{Error, RequestOptions, StreamTimeoutKind};
+
+    let client = OpenAI::builder()
+        .api_key("sk-test".into())
+        .transport(Stalling {
+            delay: Duration::from_millis(200),
+        })
+        .stream_idle_timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    // The first event arrives, then the stream stalls.
+    let mut stream = client.chat_completion_stream(request()).await.unwrap();
+    assert!(stream.next().await.unwrap().is_ok());
+    match stream.next().await {
+        Some(Err(Error::StreamTimeout { kind, after })) => {
+            assert_eq!(kind, StreamTimeoutKind::Idle);
+            assert_eq!(after, Duration::from_millis(50));
+        }
+        other => panic!("expected idle timeout, got {other:?}"),
+    }
+
+    // Headers take longer than the per-call first-byte budget.
+    let opts = RequestOptions::new().stream_first_byte_timeout(Duration::from_millis(50));
+    let err = client
+        .chat_completion_stream_with_options(request(), opts)
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        matches!(
+            err,
+            Error::StreamTimeout {
+                kind: StreamTimeoutKind::FirstByte,
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+}
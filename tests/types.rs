@@ -0,0 +1,96 @@
+use openai_sdk_rs::types::{
+    chat::{ChatCompletionRequest, ChatCompletionResponse, ChatMessage, ReasoningEffort, Role},
+    files::FileObject,
+    responses::ResponsesRequest,
+};
+use serde_json::json;
+
+#[test]
+fn unknown_response_fields_round_trip() {
+    let body = json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "gpt-4o-mini",
+        "system_fingerprint": "fp_1",
+        "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4},
+        "choices": [{
+            "index": 0,
+            "logprobs": null,
+            "finish_reason": "stop",
+            "message": {"role": "assistant", "content": "hi"}
+        }]
+    });
+    let resp: ChatCompletionResponse = serde_json::from_value(body.clone()).unwrap();
+    assert_eq!(resp.extra["system_fingerprint"], "fp_1");
+    assert!(resp.choices[0].extra.contains_key("logprobs"));
+    assert_eq!(resp.first_choice_text(), Some("hi"));
+    assert_eq!(serde_json::to_value(&resp).unwrap(), body);
+
+    let file = json!({
+        "id": "file-1", "object": "file", "bytes": 1, "created_at": 0,
+        "filename": "a.jsonl", "purpose": "batch", "status": "processed", "expires_at": 99
+    });
+    let parsed: FileObject = serde_json::from_value(file.clone()).unwrap();
+    assert_eq!(parsed.extra["status"], "processed");
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), file);
+}
+
+#[test]
+fn echoed_messages_carry_only_request_fields() {
+    let message: ChatMessage = serde_json::from_value(json!({
+        "role": "assistant", "content": "hi", "refusal": null, "annotations": []
+    }))
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(&message).unwrap(),
+        json!({"role": "assistant", "content": "hi"})
+    );
+}
+
+#[test]
+fn unknown_enum_variants_are_kept() {
+    let role: Role = serde_json::from_value(json!("developer")).unwrap();
+    assert!(matches!(&role, Role::Other(r) if r == "developer"));
+    assert_eq!(serde_json::to_value(&role).unwrap(), "developer");
+    assert!(matches!(
+        serde_json::from_value(json!("assistant")).unwrap(),
+        Role::Assistant
+    ));
+
+    let effort: ReasoningEffort = serde_json::from_value(json!("minimal")).unwrap();
+    assert_eq!(effort, ReasoningEffort::Other("minimal".into()));
+    assert_eq!(
+        serde_json::from_value::<ReasoningEffort>(json!("high")).unwrap(),
+        ReasoningEffort::High
+    );
+}
+
+#[test]
+fn extra_body_is_serialized_with_the_typed_fields() {
+    let req = ChatCompletionRequest {
+        model: "deepseek-chat".into(),
+        messages: vec![ChatMessage::user("hi")],
+        ..Default::default()
+    }
+    .temperature(0.2)
+    .body_field("top_k", 40)
+    .body_field("chat_template_kwargs", json!({"enable_thinking": false}))
+    .body_field("temperature", 0.7);
+    let body = serde_json::to_value(&req).unwrap();
+    assert_eq!(body["model"], "deepseek-chat");
+    assert_eq!(body["top_k"], 40);
+    assert_eq!(body["chat_template_kwargs"]["enable_thinking"], false);
+    assert_eq!(body["temperature"], 0.7);
+
+    let req: ResponsesRequest = serde_json::from_value(json!({
+        "model": "gpt-4o-mini", "input": "hi", "provider": {"order": ["azure"]}
+    }))
+    .unwrap();
+    assert_eq!(req.extra_body["provider"]["order"][0], "azure");
+    let req = req.extra_body(Default::default());
+    assert_eq!(
+        serde_json::to_value(&req).unwrap(),
+        json!({"model": "gpt-4o-mini", "input": "hi"})
+    );
+}